@@ -0,0 +1,269 @@
+use std::{any::type_name, fmt::Debug, path::Path};
+
+use chrono::{Datelike, NaiveDate};
+use color_eyre::eyre::eyre;
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    config::{Config, CsvLayout},
+    datasets::AnyDataset,
+    io::{relative_map_path, Serialization},
+    parse::{
+        AnyMap, DateMap, HeightMap, SerializedDateMap, SerializedHeightMap,
+        SerializedProvisionalDateMap, SerializedProvisionalHeightMap, WNaiveDate,
+        HEIGHT_MAP_CHUNK_SIZE,
+    },
+};
+
+pub fn genesis_date() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2009, 1, 3).unwrap()
+}
+
+/// A map as a column of a table, named after its path relative to the datasets folder
+pub struct Column<'a> {
+    pub name: String,
+    pub path: &'a str,
+    pub t_name: &'a str,
+    pub csv_layout: Option<CsvLayout>,
+}
+
+/// Values of a column over a range of heights or dates, `None` where its map has none
+pub enum Cells {
+    F32(Vec<Option<f32>>),
+    F64(Vec<Option<f64>>),
+    U32(Vec<Option<u32>>),
+    U64(Vec<Option<u64>>),
+    Date(Vec<Option<NaiveDate>>),
+}
+
+impl Cells {
+    pub fn to_csv_cell(&self, index: usize) -> Option<String> {
+        match self {
+            Self::F32(cells) => cells[index].map(|value| value.to_string()),
+            Self::F64(cells) => cells[index].map(|value| value.to_string()),
+            Self::U32(cells) => cells[index].map(|value| value.to_string()),
+            Self::U64(cells) => cells[index].map(|value| value.to_string()),
+            Self::Date(cells) => cells[index].map(|date| date.to_string()),
+        }
+    }
+}
+
+/// Types of the maps that can be exported as a column
+trait CellValue:
+    Copy
+    + Default
+    + Debug
+    + Serialize
+    + DeserializeOwned
+    + savefile::Serialize
+    + savefile::Deserialize
+    + savefile::ReprC
+{
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells;
+}
+
+impl CellValue for f32 {
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells {
+        Cells::F32(cells)
+    }
+}
+
+impl CellValue for f64 {
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells {
+        Cells::F64(cells)
+    }
+}
+
+impl CellValue for u32 {
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells {
+        Cells::U32(cells)
+    }
+}
+
+impl CellValue for u64 {
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells {
+        Cells::U64(cells)
+    }
+}
+
+impl CellValue for usize {
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells {
+        Cells::U64(
+            cells
+                .into_iter()
+                .map(|cell| cell.map(|value| value as u64))
+                .collect(),
+        )
+    }
+}
+
+impl CellValue for WNaiveDate {
+    fn to_cells(cells: Vec<Option<Self>>) -> Cells {
+        Cells::Date(
+            cells
+                .into_iter()
+                .map(|cell| cell.map(|date| *date))
+                .collect(),
+        )
+    }
+}
+
+impl<'a> Column<'a> {
+    pub fn from_height_maps(dataset: &'a (dyn AnyDataset + Send + Sync)) -> Vec<Self> {
+        Self::from_maps(
+            dataset
+                .to_any_inserted_height_map_vec()
+                .into_iter()
+                .map(|map| map.as_any_map()),
+        )
+    }
+
+    pub fn from_date_maps(dataset: &'a (dyn AnyDataset + Send + Sync)) -> Vec<Self> {
+        Self::from_maps(
+            dataset
+                .to_any_inserted_date_map_vec()
+                .into_iter()
+                .map(|map| map.as_any_map()),
+        )
+    }
+
+    fn from_maps(maps: impl Iterator<Item = &'a (dyn AnyMap + Send + Sync)>) -> Vec<Self> {
+        maps.map(|map| {
+            let path = map.path();
+
+            let name = relative_map_path(path)
+                .unwrap_or_else(|| path.to_owned())
+                .trim_end_matches("/height")
+                .trim_end_matches("/date")
+                .to_owned();
+
+            Self {
+                path,
+                t_name: map.t_name(),
+                csv_layout: Config::get().csv_layout(&name),
+                name,
+            }
+        })
+        .unique_by(|column| column.name.clone())
+        .collect()
+    }
+
+    /// Values from `start` to `end` (excluded)
+    pub fn read_heights(&self, start: usize, end: usize) -> color_eyre::Result<Cells> {
+        match self.t_name {
+            "f32" => read_heights::<f32>(self.path, start, end),
+            "f64" => read_heights::<f64>(self.path, start, end),
+            "u32" => read_heights::<u32>(self.path, start, end),
+            "u64" => read_heights::<u64>(self.path, start, end),
+            "usize" => read_heights::<usize>(self.path, start, end),
+            t_name if t_name == type_name::<WNaiveDate>() => {
+                read_heights::<WNaiveDate>(self.path, start, end)
+            }
+            t_name => Err(eyre!("Unsupported type: {t_name}")),
+        }
+    }
+
+    /// Values from `start` to `end` (excluded)
+    pub fn read_dates(&self, start: NaiveDate, end: NaiveDate) -> color_eyre::Result<Cells> {
+        match self.t_name {
+            "f32" => read_dates::<f32>(self.path, start, end),
+            "f64" => read_dates::<f64>(self.path, start, end),
+            "u32" => read_dates::<u32>(self.path, start, end),
+            "u64" => read_dates::<u64>(self.path, start, end),
+            "usize" => read_dates::<usize>(self.path, start, end),
+            t_name if t_name == type_name::<WNaiveDate>() => {
+                read_dates::<WNaiveDate>(self.path, start, end)
+            }
+            t_name => Err(eyre!("Unsupported type: {t_name}")),
+        }
+    }
+}
+
+/// Straight from the chunks and the provisional layer of the map on disk
+fn read_heights<T>(path: &str, start: usize, end: usize) -> color_eyre::Result<Cells>
+where
+    T: CellValue,
+{
+    let mut cells = vec![None; end - start];
+
+    let Some(serialization) = Serialization::find(path)? else {
+        return Ok(T::to_cells(cells));
+    };
+
+    let mut insert = |height: usize, value: T| {
+        if (start..end).contains(&height) {
+            cells[height - start] = Some(value);
+        }
+    };
+
+    let first_chunk_start = start / HEIGHT_MAP_CHUNK_SIZE * HEIGHT_MAP_CHUNK_SIZE;
+
+    for (chunk_start, chunk_path) in
+        HeightMap::<T>::_read_dir(path, &serialization).range(first_chunk_start..end)
+    {
+        serialization
+            .import::<SerializedHeightMap<T>>(chunk_path.to_str().unwrap())?
+            .map
+            .into_iter()
+            .enumerate()
+            .for_each(|(index, value)| insert(chunk_start + index, value));
+    }
+
+    let provisional_path = serialization.append_extension(&format!("{}/provisional", parent(path)));
+
+    if serialization.exists(&provisional_path) {
+        serialization
+            .import::<SerializedProvisionalHeightMap<T>>(&provisional_path)?
+            .map
+            .into_iter()
+            .for_each(|(height, value)| insert(height, value));
+    }
+
+    Ok(T::to_cells(cells))
+}
+
+/// Straight from the chunks and the provisional layer of the map on disk
+fn read_dates<T>(path: &str, start: NaiveDate, end: NaiveDate) -> color_eyre::Result<Cells>
+where
+    T: CellValue,
+{
+    let mut cells = vec![None; (end - start).num_days().max(0) as usize];
+
+    let Some(serialization) = Serialization::find(path)? else {
+        return Ok(T::to_cells(cells));
+    };
+
+    let mut insert = |date: NaiveDate, value: T| {
+        if (start..end).contains(&date) {
+            cells[(date - start).num_days() as usize] = Some(value);
+        }
+    };
+
+    for (_, chunk_path) in DateMap::<T>::_read_dir(path, &serialization)
+        .range(start.year() as usize..=end.year() as usize)
+    {
+        serialization
+            .import::<SerializedDateMap<T>>(chunk_path.to_str().unwrap())?
+            .map
+            .into_iter()
+            .for_each(|(date, value)| insert(*date, value));
+    }
+
+    let provisional_path =
+        serialization.append_extension(&format!("{}/date_provisional", parent(path)));
+
+    if serialization.exists(&provisional_path) {
+        serialization
+            .import::<SerializedProvisionalDateMap<T>>(&provisional_path)?
+            .map
+            .into_iter()
+            .for_each(|(date, value)| insert(*date, value));
+    }
+
+    Ok(T::to_cells(cells))
+}
+
+fn parent(path: &str) -> &str {
+    Path::new(path).parent().unwrap().to_str().unwrap()
+}
@@ -2,7 +2,14 @@ use std::thread;
 
 use chrono::{Local, NaiveDate};
 
-use crate::{databases::Databases, datasets::AllDatasets, states::States, utils::time};
+use crate::{
+    actions::{export_csv, export_parquet},
+    databases::Databases,
+    datasets::AllDatasets,
+    io::{Checkpoint, Commit, Snapshot},
+    states::States,
+    utils::time,
+};
 
 pub struct ExportedData<'a> {
     pub databases: &'a mut Databases,
@@ -12,28 +19,65 @@ pub struct ExportedData<'a> {
     pub states: &'a States,
 }
 
+///
+/// Datasets can safely be ahead of the rest, which is why they're simply saved first.
+///
+/// Databases and states are staged and then committed together by the checkpoint,
+/// if interrupted before that, the next run starts from the previous checkpoint.
+///
 pub fn export_all(
     ExportedData {
         databases,
         datasets,
+        date,
+        height,
         states,
-        ..
     }: ExportedData,
 ) -> color_eyre::Result<()> {
-    println!("{:?} - Saving... (Don't close !!)", Local::now());
+    println!("{:?} - Saving...", Local::now());
 
     time("Total save time", || -> color_eyre::Result<()> {
-        time("Datasets saved", || datasets.export())?;
+        let commit = Commit { height, date };
 
-        thread::scope(|s| {
-            s.spawn(|| time("Databases saved", || databases.export()));
-            s.spawn(|| time("States saved", || states.export()));
-        });
+        time("Datasets saved", || export_datasets(datasets, commit))?;
 
-        Ok(())
+        thread::scope(|s| -> color_eyre::Result<()> {
+            let databases_handle = s.spawn(|| time("Databases staged", || databases.stage(height)));
+            let states_handle = s.spawn(|| time("States staged", || states.stage(height)));
+
+            databases_handle.join().unwrap()?;
+            states_handle.join().unwrap()
+        })?;
+
+        let mut checkpoint = Checkpoint::import();
+
+        checkpoint.databases.replace(commit);
+        checkpoint.states.replace(commit);
+
+        checkpoint.export()?;
+
+        checkpoint.recover()?;
+
+        time("Databases saved", || databases.export())?;
+
+        export_parquet(datasets, commit)?;
+
+        export_csv(datasets, commit)?;
+
+        Snapshot::take_if_due(commit)
     })?;
 
     println!();
 
     Ok(())
 }
+
+pub fn export_datasets(datasets: &mut AllDatasets, commit: Commit) -> color_eyre::Result<()> {
+    datasets.export()?;
+
+    let mut checkpoint = Checkpoint::import();
+
+    checkpoint.datasets.replace(commit);
+
+    checkpoint.export()
+}
@@ -0,0 +1,351 @@
+use std::{collections::BTreeMap, fs, path::Path, str::FromStr};
+
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+use rayon::prelude::*;
+
+use super::{genesis_date, Cells, Column};
+
+use crate::{
+    config::{Config, CsvLayout},
+    datasets::{AllDatasets, AnyDatasets},
+    io::{list_files, Commit, Csv},
+    parse::HEIGHT_MAP_CHUNK_SIZE,
+    utils::time,
+};
+
+const HEIGHT: &str = "height";
+const DATE: &str = "date";
+
+struct Table<'a> {
+    path: String,
+    layout: CsvLayout,
+    /// Prefix removed from the names of the columns in the header of a wide table
+    prefix: String,
+    columns: Vec<Column<'a>>,
+}
+
+///
+/// Writes the maps that have a CSV layout in `{datasets_path}/csv`, either as `path,height,value` rows
+/// in `long/height.csv` (and `path,date,value` in `long/date.csv`) or with a column per map
+/// in `wide/{dataset}/height.csv` and `wide/{dataset}/date.csv`.
+///
+/// Rows are appended after each save, sorted by key, and like the Parquet export only committed heights
+/// and complete dates are written.
+///
+pub fn export_csv(datasets: &AllDatasets, commit: Commit) -> color_eyre::Result<()> {
+    let datasets = datasets.to_any_dataset_vec();
+
+    let height_tables = Table::group(
+        HEIGHT,
+        datasets
+            .iter()
+            .map(|dataset| Column::from_height_maps(*dataset)),
+    );
+
+    let date_tables = Table::group(
+        DATE,
+        datasets
+            .iter()
+            .map(|dataset| Column::from_date_maps(*dataset)),
+    );
+
+    if height_tables.is_empty() && date_tables.is_empty() {
+        return Ok(());
+    }
+
+    time("CSV exported", || -> color_eyre::Result<()> {
+        height_tables
+            .par_iter()
+            .try_for_each(|table| table.export_heights(commit.height))?;
+
+        date_tables
+            .par_iter()
+            .try_for_each(|table| table.export_dates(commit.date))
+    })
+}
+
+/// Removes the rows from `height` or `date` onwards, since they're about to be computed again
+pub fn truncate_csv(height: Option<usize>, date: Option<NaiveDate>) -> color_eyre::Result<()> {
+    let folder = csv_folder_path();
+
+    let height = height.unwrap_or(0);
+    let date = date.unwrap_or(genesis_date());
+
+    list_files(Path::new(&folder))?
+        .into_iter()
+        .try_for_each(|path| -> color_eyre::Result<()> {
+            let path = path.to_str().unwrap();
+
+            let Some(header) = Csv::read_header(path)? else {
+                return Ok(());
+            };
+
+            let header = header.split(',').collect_vec();
+
+            if let Some(index) = header.iter().position(|name| *name == HEIGHT) {
+                Csv::truncate(path, |row| {
+                    parse_key::<usize>(row, index).is_some_and(|key| key < height)
+                })
+            } else if let Some(index) = header.iter().position(|name| *name == DATE) {
+                Csv::truncate(path, |row| {
+                    parse_key::<NaiveDate>(row, index).is_some_and(|key| key < date)
+                })
+            } else {
+                Ok(())
+            }
+        })
+}
+
+fn csv_folder_path() -> String {
+    format!("{}/csv", Config::get().datasets_path)
+}
+
+impl<'a> Table<'a> {
+    /// All the long columns go in one table and the wide ones in the table of their dataset,
+    /// named after the folder their maps have in common
+    fn group(key_name: &str, datasets: impl Iterator<Item = Vec<Column<'a>>>) -> Vec<Self> {
+        let folder = csv_folder_path();
+
+        let mut long = vec![];
+        let mut wide: BTreeMap<String, Vec<Column>> = BTreeMap::new();
+
+        datasets.for_each(|columns| {
+            let (long_columns, wide_columns): (Vec<_>, Vec<_>) = columns
+                .into_iter()
+                .filter(|column| column.csv_layout.is_some())
+                .partition(|column| column.csv_layout == Some(CsvLayout::Long));
+
+            long.extend(long_columns);
+
+            if !wide_columns.is_empty() {
+                wide.entry(common_folder(&wide_columns))
+                    .or_default()
+                    .extend(wide_columns);
+            }
+        });
+
+        let long = (!long.is_empty()).then(|| Self {
+            path: format!("{folder}/long/{key_name}.csv"),
+            layout: CsvLayout::Long,
+            prefix: String::new(),
+            columns: long,
+        });
+
+        let wide = wide.into_iter().map(|(name, columns)| Self {
+            path: format!(
+                "{folder}/wide/{}/{key_name}.csv",
+                if name.is_empty() { "root" } else { &name }
+            ),
+            layout: CsvLayout::Wide,
+            prefix: if name.is_empty() {
+                name
+            } else {
+                format!("{name}/")
+            },
+            columns,
+        });
+
+        long.into_iter()
+            .chain(wide)
+            .map(|mut table| {
+                table.columns = table
+                    .columns
+                    .into_iter()
+                    .unique_by(|column| column.name.clone())
+                    .collect();
+
+                table
+            })
+            .collect()
+    }
+
+    fn header(&self, key_name: &str) -> String {
+        match self.layout {
+            CsvLayout::Long => format!("path,{key_name},value"),
+            CsvLayout::Wide => [key_name.to_owned()]
+                .into_iter()
+                .chain(self.columns.iter().map(|column| {
+                    column
+                        .name
+                        .strip_prefix(&self.prefix)
+                        .unwrap_or(&column.name)
+                        .to_owned()
+                }))
+                .join(","),
+        }
+    }
+
+    ///
+    /// Starts over if the columns changed since the last export, so that a map added later gets its earlier rows.
+    ///
+    /// A wide table has them in its header, a long one in `{table}.columns` next to it since its header never changes.
+    ///
+    fn last_key<K>(&self, header: &str) -> color_eyre::Result<Option<K>>
+    where
+        K: FromStr,
+    {
+        let changed = match self.layout {
+            CsvLayout::Long => {
+                let columns_path = self.columns_path();
+
+                let columns = self.columns.iter().map(|column| &column.name).join("\n");
+
+                let changed = fs::read_to_string(&columns_path)
+                    .map_or(true, |stored_columns| stored_columns != columns);
+
+                if changed {
+                    fs::create_dir_all(Path::new(&columns_path).parent().unwrap())?;
+                    fs::write(&columns_path, columns)?;
+                }
+
+                changed
+            }
+            CsvLayout::Wide => {
+                Csv::read_header(&self.path)?.is_some_and(|current| current != header)
+            }
+        };
+
+        if changed && Path::new(&self.path).exists() {
+            fs::remove_file(&self.path)?;
+        }
+
+        let index = match self.layout {
+            CsvLayout::Long => 1,
+            CsvLayout::Wide => 0,
+        };
+
+        Ok(Csv::read_last_row(&self.path)?.and_then(|row| parse_key(&row, index)))
+    }
+
+    fn columns_path(&self) -> String {
+        format!("{}.columns", self.path.trim_end_matches(".csv"))
+    }
+
+    fn export_heights(&self, last_height: usize) -> color_eyre::Result<()> {
+        let header = self.header(HEIGHT);
+
+        let mut start = self
+            .last_key::<usize>(&header)?
+            .map_or(0, |height| height + 1);
+
+        while start <= last_height {
+            let end =
+                ((start / HEIGHT_MAP_CHUNK_SIZE + 1) * HEIGHT_MAP_CHUNK_SIZE).min(last_height + 1);
+
+            let cells = self
+                .columns
+                .iter()
+                .map(|column| column.read_heights(start, end))
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+
+            let keys = (start..end).map(|height| height.to_string()).collect_vec();
+
+            Csv::append(&self.path, &header, &self.rows(&keys, &cells))?;
+
+            start = end;
+        }
+
+        Ok(())
+    }
+
+    fn export_dates(&self, next_date: NaiveDate) -> color_eyre::Result<()> {
+        let header = self.header(DATE);
+
+        let mut start = self
+            .last_key::<NaiveDate>(&header)?
+            .and_then(|date| date.succ_opt())
+            .unwrap_or(genesis_date());
+
+        while start < next_date {
+            let end = NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                .unwrap()
+                .min(next_date);
+
+            let cells = self
+                .columns
+                .iter()
+                .map(|column| column.read_dates(start, end))
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+
+            let keys = start
+                .iter_days()
+                .take_while(|date| *date < end)
+                .map(|date| date.to_string())
+                .collect_vec();
+
+            Csv::append(&self.path, &header, &self.rows(&keys, &cells))?;
+
+            start = end;
+        }
+
+        Ok(())
+    }
+
+    fn rows(&self, keys: &[String], cells: &[Cells]) -> Vec<String> {
+        match self.layout {
+            CsvLayout::Long => keys
+                .iter()
+                .enumerate()
+                .flat_map(|(index, key)| {
+                    self.columns
+                        .iter()
+                        .zip(cells)
+                        .filter_map(move |(column, cells)| {
+                            let value = cells.to_csv_cell(index)?;
+
+                            Some(format!("{},{key},{value}", column.name))
+                        })
+                })
+                .collect(),
+            CsvLayout::Wide => keys
+                .iter()
+                .enumerate()
+                .map(|(index, key)| {
+                    [key.to_owned()]
+                        .into_iter()
+                        .chain(
+                            cells
+                                .iter()
+                                .map(|cells| cells.to_csv_cell(index).unwrap_or_default()),
+                        )
+                        .join(",")
+                })
+                .collect(),
+        }
+    }
+}
+
+fn parse_key<K>(row: &str, index: usize) -> Option<K>
+where
+    K: FromStr,
+{
+    row.split(',').nth(index)?.parse().ok()
+}
+
+/// Folder that the paths of all the columns have in common
+fn common_folder(columns: &[Column]) -> String {
+    let folders = columns
+        .iter()
+        .map(|column| {
+            column
+                .name
+                .rsplit_once('/')
+                .map_or("", |(folder, _)| folder)
+                .split('/')
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let first = &folders[0];
+
+    let len = (0..first.len())
+        .take_while(|index| {
+            folders
+                .iter()
+                .all(|folder| folder.get(*index) == Some(&first[*index]))
+        })
+        .count();
+
+    first[..len].join("/")
+}
@@ -0,0 +1,240 @@
+use std::{
+    fmt::Display,
+    fs::{self, File},
+    marker::PhantomData,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use arrow::{
+    array::{
+        ArrayRef, Date32Array, Float32Array, Float64Array, RecordBatch, UInt32Array, UInt64Array,
+    },
+    datatypes::{Date32Type, Field, Schema},
+};
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+use parquet::{arrow::ArrowWriter, basic::Compression, file::properties::WriterProperties};
+use rayon::prelude::*;
+
+use super::{genesis_date, Cells, Column};
+
+use crate::{
+    config::Config,
+    datasets::{AllDatasets, AnyDatasets},
+    io::{tmp_path, Commit},
+    parse::HEIGHT_MAP_CHUNK_SIZE,
+    utils::time,
+};
+
+///
+/// Writes every height map in `{parquet_path}/height` and every date map in `{parquet_path}/date`,
+/// one column per map, to be read with pandas, polars, DuckDB...
+///
+/// Parquet files can't be appended to so each save adds parts named `{start}..{end}.parquet`
+/// (`end` excluded) with the rows added since the previous one, never spanning more than one chunk.
+/// The whole table is read with a glob, like `read_parquet('height/*.parquet', union_by_name = true)`.
+///
+/// Only committed heights and complete dates are written since parts are never updated.
+///
+pub fn export_parquet(datasets: &AllDatasets, commit: Commit) -> color_eyre::Result<()> {
+    let Some(parquet_path) = Config::get().parquet_path.as_ref() else {
+        return Ok(());
+    };
+
+    time("Parquet exported", || -> color_eyre::Result<()> {
+        let datasets = datasets.to_any_dataset_vec();
+
+        let height_columns = datasets
+            .iter()
+            .flat_map(|dataset| Column::from_height_maps(*dataset))
+            .unique_by(|column| column.name.clone())
+            .collect_vec();
+
+        let date_columns = datasets
+            .iter()
+            .flat_map(|dataset| Column::from_date_maps(*dataset))
+            .unique_by(|column| column.name.clone())
+            .collect_vec();
+
+        let heights = Parts::<usize>::new(parquet_path, "height");
+
+        let mut start = heights.end()?.unwrap_or(0);
+
+        while start <= commit.height {
+            let end = ((start / HEIGHT_MAP_CHUNK_SIZE + 1) * HEIGHT_MAP_CHUNK_SIZE)
+                .min(commit.height + 1);
+
+            let columns = height_columns
+                .par_iter()
+                .map(|column| Ok(to_array(column.read_heights(start, end)?)))
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+
+            let keys = Arc::new(UInt64Array::from_iter_values(
+                (start..end).map(|height| height as u64),
+            ));
+
+            heights.write(start, end, keys, &height_columns, columns)?;
+
+            start = end;
+        }
+
+        let dates = Parts::<NaiveDate>::new(parquet_path, "date");
+
+        let mut start = dates.end()?.unwrap_or(genesis_date());
+
+        while start < commit.date {
+            let end = NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+                .unwrap()
+                .min(commit.date);
+
+            let len = (end - start).num_days() as usize;
+
+            let columns = date_columns
+                .par_iter()
+                .map(|column| Ok(to_array(column.read_dates(start, end)?)))
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+
+            let keys = Arc::new(Date32Array::from_iter_values(
+                start.iter_days().take(len).map(Date32Type::from_naive_date),
+            ));
+
+            dates.write(start, end, keys, &date_columns, columns)?;
+
+            start = end;
+        }
+
+        Ok(())
+    })
+}
+
+/// Removes the parts that have rows from `height` or `date` onwards, since they're about to be computed again
+pub fn truncate_parquet(height: Option<usize>, date: Option<NaiveDate>) -> color_eyre::Result<()> {
+    let Some(parquet_path) = Config::get().parquet_path.as_ref() else {
+        return Ok(());
+    };
+
+    Parts::<usize>::new(parquet_path, "height").truncate(height.unwrap_or(0))?;
+
+    Parts::<NaiveDate>::new(parquet_path, "date").truncate(date.unwrap_or(genesis_date()))
+}
+
+fn to_array(cells: Cells) -> ArrayRef {
+    match cells {
+        Cells::F32(cells) => Arc::new(Float32Array::from(cells)),
+        Cells::F64(cells) => Arc::new(Float64Array::from(cells)),
+        Cells::U32(cells) => Arc::new(UInt32Array::from(cells)),
+        Cells::U64(cells) => Arc::new(UInt64Array::from(cells)),
+        Cells::Date(cells) => Arc::new(
+            cells
+                .into_iter()
+                .map(|cell| cell.map(Date32Type::from_naive_date))
+                .collect::<Date32Array>(),
+        ),
+    }
+}
+
+struct Parts<K> {
+    key_name: &'static str,
+    path: String,
+    phantom: PhantomData<K>,
+}
+
+impl<K> Parts<K>
+where
+    K: Copy + Ord + Display + FromStr,
+{
+    fn new(parquet_path: &str, key_name: &'static str) -> Self {
+        Self {
+            key_name,
+            path: format!("{parquet_path}/{key_name}"),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Parts with their range, sorted by start
+    fn list(&self) -> color_eyre::Result<Vec<(K, K, PathBuf)>> {
+        if !Path::new(&self.path).exists() {
+            return Ok(vec![]);
+        }
+
+        let mut parts = vec![];
+
+        for entry in fs::read_dir(&self.path)? {
+            let path = entry?.path();
+
+            if path.extension().and_then(|extension| extension.to_str()) != Some("parquet") {
+                continue;
+            }
+
+            let range = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.split_once(".."))
+                .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)));
+
+            if let Some((start, end)) = range {
+                parts.push((start, end, path));
+            }
+        }
+
+        parts.sort_by_key(|(start, _, _)| *start);
+
+        Ok(parts)
+    }
+
+    fn end(&self) -> color_eyre::Result<Option<K>> {
+        Ok(self.list()?.into_iter().map(|(_, end, _)| end).max())
+    }
+
+    fn truncate(&self, key: K) -> color_eyre::Result<()> {
+        self.list()?
+            .into_iter()
+            .filter(|(_, end, _)| *end > key)
+            .try_for_each(|(_, _, path)| fs::remove_file(path))?;
+
+        Ok(())
+    }
+
+    fn write(
+        &self,
+        start: K,
+        end: K,
+        keys: ArrayRef,
+        columns: &[Column],
+        arrays: Vec<ArrayRef>,
+    ) -> color_eyre::Result<()> {
+        fs::create_dir_all(&self.path)?;
+
+        let fields =
+            [Field::new(self.key_name, keys.data_type().clone(), false)]
+                .into_iter()
+                .chain(columns.iter().zip(arrays.iter()).map(|(column, array)| {
+                    Field::new(&column.name, array.data_type().clone(), true)
+                }))
+                .collect::<Vec<_>>();
+
+        let batch = RecordBatch::try_new(
+            Arc::new(Schema::new(fields)),
+            [keys].into_iter().chain(arrays).collect(),
+        )?;
+
+        let path = format!("{}/{start}..{end}.parquet", self.path);
+        let tmp_path = tmp_path(&path);
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let mut writer =
+            ArrowWriter::try_new(File::create(&tmp_path)?, batch.schema(), Some(properties))?;
+
+        writer.write(&batch)?;
+        writer.close()?;
+
+        fs::rename(tmp_path, path)?;
+
+        Ok(())
+    }
+}
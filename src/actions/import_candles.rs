@@ -0,0 +1,62 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use color_eyre::eyre::eyre;
+use serde_json::Value;
+
+use crate::price::{Candle, CandleStore};
+
+const BATCH_LEN: usize = 1_000_000;
+
+///
+/// Imports 1 minute candles from CSV dumps of exchanges into the candle store, replacing the ones already stored.
+///
+/// Rows are `timestamp,open,high,low,close,volume` with optionally more columns after, like in Kraken's
+/// and Binance's dumps, and a header is skipped.
+///
+pub fn import_candles(paths: &[String]) -> color_eyre::Result<()> {
+    if paths.is_empty() {
+        return Err(eyre!("Expected at least one file"));
+    }
+
+    let mut store = CandleStore::default();
+
+    for path in paths {
+        let reader = BufReader::new(File::open(path)?);
+
+        let mut batch = Vec::with_capacity(BATCH_LEN);
+        let mut imported = 0;
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let row = line
+                .split(',')
+                .map(|cell| Value::String(cell.trim().to_owned()))
+                .collect::<Vec<_>>();
+
+            match Candle::from_row(&row, Some(5)) {
+                Some(candle) => batch.push(candle),
+                // Header
+                None if index == 0 => {}
+                None => return Err(eyre!("Invalid row {index} in {path}: {line}")),
+            }
+
+            if batch.len() == BATCH_LEN {
+                imported += store.insert(batch.drain(..), true)?;
+            }
+        }
+
+        imported += store.insert(batch, true)?;
+
+        println!("{path}: {imported} candles imported");
+    }
+
+    Ok(())
+}
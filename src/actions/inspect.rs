@@ -0,0 +1,70 @@
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+
+use crate::{
+    datasets::{AllDatasets, AnyDatasets},
+    io::format_path,
+};
+
+/// `path` is the path of a map without its `/height` or `/date` suffix, as found in `paths.json`
+pub fn inspect(
+    path: &str,
+    height: Option<usize>,
+    date: Option<NaiveDate>,
+) -> color_eyre::Result<()> {
+    if height.is_none() && date.is_none() {
+        return Err(eyre!("Expected --height and/or --date"));
+    }
+
+    let path = format_path(
+        path.trim_end_matches('/')
+            .trim_end_matches("/height")
+            .trim_end_matches("/date"),
+    );
+
+    let datasets = AllDatasets::import()?;
+
+    let mut found = false;
+
+    if let Some(height) = height {
+        let height_path = format!("{path}/height");
+
+        if let Some(map) = datasets
+            .to_any_dataset_vec()
+            .into_iter()
+            .flat_map(|dataset| dataset.to_any_inserted_height_map_vec())
+            .find(|map| map.path() == height_path)
+        {
+            found = true;
+
+            match map.inspect(height)? {
+                Some(value) => println!("{height_path} @ {height}: {value}"),
+                None => println!("{height_path} @ {height}: none"),
+            }
+        }
+    }
+
+    if let Some(date) = date {
+        let date_path = format!("{path}/date");
+
+        if let Some(map) = datasets
+            .to_any_dataset_vec()
+            .into_iter()
+            .flat_map(|dataset| dataset.to_any_inserted_date_map_vec())
+            .find(|map| map.path() == date_path)
+        {
+            found = true;
+
+            match map.inspect(date)? {
+                Some(value) => println!("{date_path} @ {date}: {value}"),
+                None => println!("{date_path} @ {date}: none"),
+            }
+        }
+    }
+
+    if !found {
+        return Err(eyre!("No map found at {path}, see paths.json for the list"));
+    }
+
+    Ok(())
+}
@@ -1,27 +1,79 @@
 use std::time::Instant;
 
-use chrono::{offset::Local, Datelike};
+use chrono::offset::Local;
+use color_eyre::eyre::eyre;
 use export_all::ExportedData;
 use parse_block::ParseData;
 
 use crate::{
-    actions::{export_all, find_first_unsafe_height, parse_block},
-    bitcoin::{check_if_height_safe, BitcoinDB, NUMBER_OF_UNSAFE_BLOCKS},
+    actions::{
+        export_all, export_datasets, find_first_unsafe_height, parse_block, rollback_if_reorg,
+        truncate_csv, truncate_parquet,
+    },
+    bitcoin::{check_if_height_safe, BlockSource},
+    config::Config,
     databases::Databases,
-    datasets::{AllDatasets, AnyDatasets},
+    datasets::{AllDatasets, AnyDatasets, MinInitialState},
+    io::{Checkpoint, Commit},
     parse::DateData,
     states::States,
     utils::timestamp_to_naive_date,
 };
 
-pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Result<()> {
-    let insert = true;
-    let export = true;
+pub fn iter_blocks(block_source: &dyn BlockSource, block_count: usize) -> color_eyre::Result<()> {
+    let Config {
+        insert,
+        export,
+        export_cadence,
+        unsafe_blocks,
+        compute_addresses: config_compute_addresses,
+        ..
+    } = *Config::get();
+
+    if unsafe_blocks >= block_count {
+        return Err(eyre!(
+            "unsafe_blocks ({unsafe_blocks}) needs to be lower than the block count ({block_count})"
+        ));
+    }
 
     println!("{:?} - Starting aged", Local::now());
 
+    Checkpoint::import().recover()?;
+
     let mut datasets = AllDatasets::import()?;
 
+    println!("{:?} - Imported datasets", Local::now());
+
+    let mut databases = Databases::import();
+
+    println!("{:?} - Imported databases", Local::now());
+
+    let mut states = States::import().unwrap_or_default();
+
+    println!("{:?} - Imported states", Local::now());
+
+    rollback_if_reorg(block_source, &mut states, &mut databases, &mut datasets)?;
+
+    let MinInitialState {
+        first_unsafe_height,
+        first_unsafe_date,
+        ..
+    } = *datasets.get_min_initial_state();
+
+    truncate_parquet(first_unsafe_height, first_unsafe_date)?;
+    truncate_csv(first_unsafe_height, first_unsafe_date)?;
+
+    let first_provisional_height = block_count.checked_sub(unsafe_blocks);
+
+    let first_provisional_date = first_provisional_height
+        .map(|height| -> color_eyre::Result<_> {
+            let block = block_source.get_block(height)?;
+            Ok(timestamp_to_naive_date(block.header.time))
+        })
+        .transpose()?;
+
+    datasets.set_first_provisional_height(first_provisional_height, first_provisional_date);
+
     let min_initial_first_unsafe_address_date = datasets
         .address
         .get_min_initial_state()
@@ -36,21 +88,11 @@ pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Re
         .as_ref()
         .cloned();
 
-    println!("{:?} - Imported datasets", Local::now());
-
-    let mut databases = Databases::import();
-
-    println!("{:?} - Imported databases", Local::now());
-
-    let mut states = States::import().unwrap_or_default();
-
-    println!("{:?} - Imported states", Local::now());
-
-    let mut height = find_first_unsafe_height(&mut states, &mut databases, &datasets);
+    let mut height = find_first_unsafe_height(&mut states, &mut databases, &datasets)?;
 
     println!("{:?} - Starting parsing at height: {height}", Local::now());
 
-    let mut block_iter = bitcoin_db.iter_block(height, block_count);
+    let mut block_iter = block_source.iter_block(height, block_count);
 
     let mut next_block_opt = None;
     let mut blocks_loop_date = None;
@@ -66,9 +108,14 @@ pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Re
             }
 
             'blocks: loop {
-                let current_block_opt = next_block_opt.take().or_else(|| block_iter.next());
+                // A block that couldn't be fetched fails the pass instead of being taken for the tip,
+                // which would store the values of its date as if it was complete
+                let current_block_opt = match next_block_opt.take() {
+                    Some(block) => Some(block),
+                    None => block_iter.next().transpose()?,
+                };
 
-                next_block_opt = block_iter.next();
+                next_block_opt = block_iter.next().transpose()?;
 
                 if let Some(current_block) = current_block_opt {
                     let timestamp = current_block.header.time;
@@ -109,22 +156,20 @@ pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Re
 
                     let is_date_last_block = next_block_date
                         // Do NOT change `blocks_loop_date` to `current_block_date` !!!
-                        .map_or(true, |next_block_date| blocks_loop_date < next_block_date);
-
-                    let compute_addresses = min_initial_first_unsafe_address_date
-                        .map_or(true, |min_initial_unsafe_date| {
-                            current_block_date >= min_initial_unsafe_date
-                        })
-                        || min_initial_first_unsafe_address_height.map_or(
-                            true,
+                        .is_none_or(|next_block_date| blocks_loop_date < next_block_date);
+
+                    let compute_addresses = config_compute_addresses
+                        && (min_initial_first_unsafe_address_date.is_none_or(
+                            |min_initial_unsafe_date| current_block_date >= min_initial_unsafe_date,
+                        ) || min_initial_first_unsafe_address_height.is_none_or(
                             |min_initial_unsafe_height| {
                                 current_block_height >= min_initial_unsafe_height
                             },
-                        );
+                        ));
 
                     if insert {
                         parse_block(ParseData {
-                            bitcoin_db,
+                            block_source,
                             block: current_block,
                             block_index: blocks_loop_i,
                             compute_addresses,
@@ -136,7 +181,7 @@ pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Re
                             is_date_last_block,
                             states: &mut states,
                             timestamp,
-                        });
+                        })?;
                     }
 
                     blocks_loop_i += 1;
@@ -144,13 +189,14 @@ pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Re
                     if is_date_last_block {
                         height += blocks_loop_i;
 
-                        let is_new_month = next_block_date
-                            .map_or(true, |next_block_date| next_block_date.day() == 1);
+                        let is_new_period = next_block_date.is_none_or(|next_block_date| {
+                            export_cadence.is_new_period(blocks_loop_date, next_block_date)
+                        });
 
                         let is_close_to_the_end =
-                            height > (block_count - (NUMBER_OF_UNSAFE_BLOCKS * 3));
+                            height > block_count.saturating_sub(unsafe_blocks * 3);
 
-                        if is_new_month || is_close_to_the_end {
+                        if is_new_period || is_close_to_the_end {
                             break 'days;
                         }
 
@@ -165,23 +211,44 @@ pub fn iter_blocks(bitcoin_db: &BitcoinDB, block_count: usize) -> color_eyre::Re
         let last_height = height - 1;
 
         println!(
-            "Parsing month took {} seconds (last height: {last_height})\n",
+            "Parsing {} took {} seconds (last height: {last_height})\n",
+            export_cadence.name(),
             time.elapsed().as_secs_f32(),
         );
 
-        if export && check_if_height_safe(height, block_count) {
-            export_all(ExportedData {
-                databases: &mut databases,
-                datasets: &mut datasets,
-                date: blocks_loop_date.unwrap(),
-                height: last_height,
-                states: &states,
-            })?;
+        if export {
+            if check_if_height_safe(height, block_count) {
+                export_all(ExportedData {
+                    databases: &mut databases,
+                    datasets: &mut datasets,
+                    date: blocks_loop_date.unwrap(),
+                    height: last_height,
+                    states: &states,
+                })?;
+            } else {
+                // States stay at the last safe height so that unsafe blocks get parsed again next time
+                export_datasets(
+                    &mut datasets,
+                    Commit {
+                        height: last_height,
+                        date: blocks_loop_date.unwrap(),
+                    },
+                )?;
+            }
         }
     }
 
     if export {
-        datasets.export()?;
+        match blocks_loop_date {
+            Some(date) => export_datasets(
+                &mut datasets,
+                Commit {
+                    height: height - 1,
+                    date,
+                },
+            )?,
+            None => datasets.export()?,
+        }
     }
 
     Ok(())
@@ -1,6 +1,7 @@
 use crate::{
     databases::Databases,
     datasets::{AllDatasets, AnyDatasets},
+    io::Snapshot,
     states::States,
 };
 
@@ -8,11 +9,48 @@ pub fn find_first_unsafe_height(
     states: &mut States,
     databases: &mut Databases,
     datasets: &AllDatasets,
-) -> usize {
+) -> color_eyre::Result<usize> {
     let min_initial_last_address_date = datasets.address.get_min_initial_state().last_date;
 
     let min_initial_last_address_height = datasets.address.get_min_initial_state().last_height;
 
+    if let Some(height) = compute_first_unsafe_height(states, datasets) {
+        return Ok(height);
+    }
+
+    // Only the datasets that are behind will insert, the others being skipped by `should_insert`
+    if let Some(snapshot) = Snapshot::find_replayable(datasets.get_min_initial_state()) {
+        println!(
+            "Replaying outdated datasets from the snapshot at height {}...",
+            snapshot.commit.height
+        );
+
+        snapshot.restore()?;
+
+        // Fails for snapshots taken before a change in the layout of the states
+        if let Ok(imported) = States::import() {
+            *states = imported;
+            *databases = Databases::import();
+
+            return Ok(snapshot.commit.height + 1);
+        }
+
+        println!("Couldn't import the states of the snapshot");
+    }
+
+    println!("Starting over...");
+
+    states.reset();
+
+    databases.reset(true);
+    // Doesn't always work as intended
+    // databases.reset(min_initial_last_address_date.is_none() || min_initial_last_address_height.is_none());
+
+    Ok(0)
+}
+
+/// Returns `None` if the states and datasets are out of sync and everything needs to be parsed again
+pub fn compute_first_unsafe_height(states: &States, datasets: &AllDatasets) -> Option<usize> {
     states
         .date_data_vec
         .iter()
@@ -44,16 +82,4 @@ pub fn find_first_unsafe_height(
                 }
             )
         })
-        .unwrap_or_else(|| {
-            println!("Starting over...");
-
-            states.reset();
-
-
-            databases.reset(true);
-            // Doesn't always work as intended
-            // databases.reset(min_initial_last_address_date.is_none() || min_initial_last_address_height.is_none());
-
-            0
-        })
 }
@@ -1,9 +1,25 @@
+mod columns;
 mod export_all;
+mod export_csv;
+mod export_parquet;
+mod import_candles;
+mod inspect;
 mod iter_blocks;
 mod min_height;
 mod parse_block;
+mod reorg;
+mod reset;
+mod verify;
 
+pub use columns::*;
 pub use export_all::*;
+pub use export_csv::*;
+pub use export_parquet::*;
+pub use import_candles::*;
+pub use inspect::*;
 pub use iter_blocks::*;
 pub use min_height::*;
 pub use parse_block::*;
+pub use reorg::*;
+pub use reset::*;
+pub use verify::*;
@@ -4,13 +4,13 @@ use std::{
     thread,
 };
 
-use bitcoin::Block;
+use bitcoin::{Block, Txid};
 use chrono::NaiveDate;
 use itertools::Itertools;
 use rayon::prelude::*;
 
 use crate::{
-    bitcoin::BitcoinDB,
+    bitcoin::BlockSource,
     databases::{AddressIndexToEmptyAddressData, AddressToAddressIndex, Databases, TxidToTxIndex},
     datasets::{AllDatasets, ProcessedBlockData},
     parse::{
@@ -24,7 +24,7 @@ use crate::{
 };
 
 pub struct ParseData<'a> {
-    pub bitcoin_db: &'a BitcoinDB,
+    pub block_source: &'a dyn BlockSource,
     pub block: Block,
     pub block_index: usize,
     pub compute_addresses: bool,
@@ -66,7 +66,7 @@ impl ReceivedData {
 
 pub fn parse_block(
     ParseData {
-        bitcoin_db,
+        block_source,
         block,
         block_index,
         compute_addresses,
@@ -79,10 +79,18 @@ pub fn parse_block(
         states,
         timestamp,
     }: ParseData,
-) {
+) -> color_eyre::Result<()> {
     // If false, expect that the code is flawless
     let enable_check_if_txout_value_is_zero_in_db: bool = true;
 
+    let check_if_txout_value_is_zero = |txid: Txid, vout: u32| {
+        if enable_check_if_txout_value_is_zero_in_db {
+            block_source.check_if_txout_value_is_zero(&txid, vout)
+        } else {
+            Ok(true)
+        }
+    };
+
     let date_index = states.date_data_vec.len() - 1;
 
     let block_path = BlockPath {
@@ -90,15 +98,9 @@ pub fn parse_block(
         block_index: block_index as u16,
     };
 
-    let block_price = datasets
-        .price
-        .height_to_close(height, timestamp)
-        .unwrap_or_else(|_| panic!("Expect {height} to have a price"));
+    let block_price = datasets.price.height_to_close(height, timestamp)?;
 
-    let date_price = datasets
-        .price
-        .date_to_close(date)
-        .unwrap_or_else(|_| panic!("Expect {date} to have a price"));
+    let date_price = datasets.price.date_to_close(date)?;
 
     states
         .date_data_vec
@@ -107,6 +109,8 @@ pub fn parse_block(
         .blocks
         .push(BlockData::new(height as u32, block_price, timestamp));
 
+    states.block_hashes.insert(height, block.block_hash());
+
     let mut block_path_to_spent_data: BTreeMap<BlockPath, SpentData> = BTreeMap::new();
     let mut block_path_to_received_data: BTreeMap<BlockPath, ReceivedData> = BTreeMap::new();
     let mut address_index_to_address_realized_data: BTreeMap<u32, AddressRealizedData> =
@@ -173,7 +177,7 @@ pub fn parse_block(
         (output_handle.join().unwrap(), input_handle.join().unwrap())
     });
 
-    block.txdata.into_iter().try_for_each(|tx| {
+    let flow = block.txdata.into_iter().try_for_each(|tx| {
         let txid = tx.txid();
         let txs_counter = &mut databases.txid_to_tx_index.metadata.len;
         let tx_index = txs_counter.inner();
@@ -370,10 +374,10 @@ pub fn parse_block(
                     });
 
                     if input_tx_index.is_none() {
-                        if !enable_check_if_txout_value_is_zero_in_db
-                            || bitcoin_db.check_if_txout_value_is_zero(&input_txid, input_vout)
-                        {
-                            return ControlFlow::Continue::<()>(());
+                        match check_if_txout_value_is_zero(input_txid, input_vout) {
+                            Ok(true) => return ControlFlow::Continue(()),
+                            Ok(false) => {}
+                            Err(error) => return ControlFlow::Break(error),
                         }
 
                         dbg!((input_txid, txid, tx_index, input_vout));
@@ -387,10 +391,10 @@ pub fn parse_block(
                     let input_sats = states.txout_index_to_sats.remove(&input_txout_index);
 
                     if input_sats.is_none() {
-                        if !enable_check_if_txout_value_is_zero_in_db
-                            || bitcoin_db.check_if_txout_value_is_zero(&input_txid, input_vout)
-                        {
-                            return ControlFlow::Continue::<()>(());
+                        match check_if_txout_value_is_zero(input_txid, input_vout) {
+                            Ok(true) => return ControlFlow::Continue(()),
+                            Ok(false) => {}
+                            Err(error) => return ControlFlow::Break(error),
                         }
 
                         dbg!((input_txid, tx_index, input_tx_index, input_vout, input_sats,));
@@ -532,6 +536,10 @@ pub fn parse_block(
         ControlFlow::Continue(())
     });
 
+    if let ControlFlow::Break(error) = flow {
+        return Err(error);
+    }
+
     let mut utxo_cohorts_sent_states = UTXOCohortsSentStates::default();
     let mut utxo_cohorts_one_shot_states = UTXOCohortsOneShotStates::default();
     let mut utxo_cohorts_received_states = UTXOCohortsReceivedStates::default();
@@ -675,7 +683,7 @@ pub fn parse_block(
         utxo_cohorts_one_shot_states: &utxo_cohorts_one_shot_states,
         utxo_cohorts_received_states: &utxo_cohorts_received_states,
         utxo_cohorts_sent_states: &utxo_cohorts_sent_states,
-    });
+    })
 }
 
 pub struct TxoutsParsingResults {
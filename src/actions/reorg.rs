@@ -0,0 +1,99 @@
+use chrono::Local;
+use color_eyre::eyre::eyre;
+
+use crate::{
+    bitcoin::BlockSource, databases::Databases, datasets::AllDatasets, io::Snapshot, states::States,
+};
+
+/// Returns the height of the first block that isn't in the best chain anymore, if any
+pub fn find_fork_height(
+    states: &States,
+    block_source: &dyn BlockSource,
+) -> color_eyre::Result<Option<usize>> {
+    let block_count = block_source.get_block_count()?;
+
+    let mut fork_height = None;
+
+    for height in (0..states.block_hashes.len()).rev() {
+        let Some(hash) = states.block_hashes.get_hash(height) else {
+            continue;
+        };
+
+        if height < block_count && block_source.get_block_hash(height)? == hash {
+            break;
+        }
+
+        fork_height.replace(height);
+    }
+
+    Ok(fork_height)
+}
+
+///
+/// States can't be reverted block by block, so they're restored with the databases from the most recent snapshot
+/// before the fork, or parsed again from scratch if there's none.
+///
+/// Datasets are truncated at the fork, what they computed between the snapshot and the fork being kept.
+///
+pub fn rollback_if_reorg(
+    block_source: &dyn BlockSource,
+    states: &mut States,
+    databases: &mut Databases,
+    datasets: &mut AllDatasets,
+) -> color_eyre::Result<()> {
+    let Some(fork_height) = find_fork_height(states, block_source)? else {
+        return Ok(());
+    };
+
+    println!(
+        "{:?} - Reorg detected at height: {fork_height}",
+        Local::now()
+    );
+
+    let fork_date = states
+        .date_data_vec
+        .iter()
+        .find(|date_data| {
+            date_data
+                .blocks
+                .iter()
+                .any(|block| block.height as usize == fork_height)
+        })
+        .map(|date_data| *date_data.date)
+        .ok_or(eyre!("Couldn't find the date of the fork at {fork_height}"))?;
+
+    datasets.rollback(fork_height, fork_date)?;
+
+    *datasets = AllDatasets::import()?;
+
+    if let Some(snapshot) = Snapshot::list()
+        .into_iter()
+        .rev()
+        .find(|snapshot| snapshot.commit.height < fork_height)
+    {
+        println!(
+            "Rolling back states and databases to the snapshot at height {}...",
+            snapshot.commit.height
+        );
+
+        snapshot.restore()?;
+
+        // Fails for snapshots taken before a change in the layout of the states
+        if let Ok(imported) = States::import() {
+            *states = imported;
+            *databases = Databases::import();
+
+            return Ok(());
+        }
+
+        println!("Couldn't import the states of the snapshot");
+    }
+
+    println!("No snapshot before the fork, starting over...");
+
+    states.reset();
+
+    databases.reset(true);
+
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use color_eyre::eyre::eyre;
+
+use crate::{databases::Databases, datasets::AllDatasets, states::States};
+
+pub struct ResetTargets {
+    pub databases: bool,
+    pub datasets: bool,
+    pub states: bool,
+}
+
+pub fn reset(
+    ResetTargets {
+        databases,
+        datasets,
+        states,
+    }: ResetTargets,
+) -> color_eyre::Result<()> {
+    if !states && !databases && !datasets {
+        return Err(eyre!(
+            "Expected at least one of --states, --databases or --datasets"
+        ));
+    }
+
+    if states {
+        States::import().unwrap_or_default().reset();
+    }
+
+    if databases {
+        Databases::import().reset(true);
+    }
+
+    if datasets {
+        AllDatasets::import()?.reset()?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use itertools::Itertools;
+
+use crate::{
+    actions::compute_first_unsafe_height,
+    databases::Databases,
+    datasets::{AllDatasets, AnyDatasets},
+    io::{Checkpoint, Snapshot},
+    states::States,
+};
+
+/// Checks that states, databases and datasets are in sync without modifying anything
+///
+/// Returns `false` if the next parse would have to start over
+pub fn verify() -> color_eyre::Result<bool> {
+    let datasets = AllDatasets::import()?;
+
+    let databases = Databases::import();
+
+    let states = match States::import() {
+        Ok(states) => states,
+        Err(error) => {
+            println!("States: couldn't be imported ({error})");
+            States::default()
+        }
+    };
+
+    let last_state_date = states.date_data_vec.last().map(|date_data| *date_data.date);
+    let last_state_height = states
+        .date_data_vec
+        .last()
+        .and_then(|date_data| date_data.blocks.last())
+        .map(|block_data| block_data.height as usize);
+
+    println!("Checkpoint: {:?}", Checkpoint::import());
+
+    println!("States: last date: {last_state_date:?}, last height: {last_state_height:?}");
+
+    println!(
+        "Databases: {} txs, {} addresses, {} empty addresses",
+        databases.txid_to_tx_index.metadata.len.inner(),
+        databases.address_to_address_index.metadata.len.inner(),
+        databases
+            .address_index_to_empty_address_data
+            .metadata
+            .len
+            .inner(),
+    );
+
+    let min_initial_state = datasets.get_min_initial_state();
+
+    println!(
+        "Datasets: last date: {:?}, last height: {:?}",
+        min_initial_state.last_date, min_initial_state.last_height
+    );
+
+    datasets
+        .to_any_dataset_vec()
+        .into_iter()
+        .flat_map(|dataset| {
+            dataset
+                .to_any_inserted_height_map_vec()
+                .into_iter()
+                .filter(|map| is_behind(map.get_initial_last_height(), last_state_height))
+                .map(|map| map.path().to_owned())
+                .chain(
+                    dataset
+                        .to_any_inserted_date_map_vec()
+                        .into_iter()
+                        .filter(|map| is_behind(map.get_initial_last_date(), last_state_date))
+                        .map(|map| map.path().to_owned()),
+                )
+                .collect_vec()
+        })
+        .for_each(|path| println!("  {path} is behind the states"));
+
+    match compute_first_unsafe_height(&states, &datasets) {
+        Some(height) => {
+            println!("OK, parsing would resume at height {height}");
+
+            Ok(true)
+        }
+        None => match Snapshot::find_replayable(datasets.get_min_initial_state()) {
+            Some(snapshot) => {
+                println!(
+                    "Behind, parsing would replay outdated datasets from height {}",
+                    snapshot.commit.height + 1
+                );
+
+                Ok(false)
+            }
+            None => {
+                println!("Out of sync, parsing would start over");
+
+                Ok(false)
+            }
+        },
+    }
+}
+
+fn is_behind<T>(map_last: Option<T>, states_last: Option<T>) -> bool
+where
+    T: PartialOrd,
+{
+    states_last.is_some_and(|states_last| map_last.is_none_or(|map_last| map_last < states_last))
+}
@@ -0,0 +1,20 @@
+use clap::Parser;
+use parser::{serve, Args, Config};
+
+/// Serves the exported datasets over HTTP
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    #[command(flatten)]
+    args: Args,
+}
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    let Cli { args } = Cli::parse();
+
+    Config::from_args(args)?.init();
+
+    serve()
+}
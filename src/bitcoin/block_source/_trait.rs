@@ -0,0 +1,29 @@
+use bitcoin::{Block, BlockHash, Transaction, Txid};
+use color_eyre::eyre::eyre;
+
+use crate::bitcoin::BlockIter;
+
+pub trait BlockSource: Send + Sync {
+    /// Blocks from 0 to `get_block_count() - 1` are available
+    fn get_block_count(&self) -> color_eyre::Result<usize>;
+
+    fn get_block_hash(&self, height: usize) -> color_eyre::Result<BlockHash>;
+
+    fn get_block(&self, height: usize) -> color_eyre::Result<Block>;
+
+    fn get_transaction(&self, txid: &Txid) -> color_eyre::Result<Transaction>;
+
+    /// Iterate through all blocks from `start` to `end` (excluded), fetched in parallel but returned in order
+    fn iter_block(&self, start: usize, end: usize) -> BlockIter;
+
+    fn check_if_txout_value_is_zero(&self, txid: &Txid, vout: u32) -> color_eyre::Result<bool> {
+        let tx = self.get_transaction(txid)?;
+
+        let txout = tx
+            .output
+            .get(vout as usize)
+            .ok_or_else(|| eyre!("Transaction {txid} has no output {vout}"))?;
+
+        Ok(txout.value.to_sat() == 0)
+    }
+}
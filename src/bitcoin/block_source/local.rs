@@ -0,0 +1,31 @@
+use bitcoin::{Block, BlockHash, Transaction, Txid};
+use color_eyre::eyre::eyre;
+
+use crate::bitcoin::{BitcoinDB, BlockIter};
+
+use super::BlockSource;
+
+impl BlockSource for BitcoinDB {
+    fn get_block_count(&self) -> color_eyre::Result<usize> {
+        Ok(BitcoinDB::get_block_count(self))
+    }
+
+    fn get_block_hash(&self, height: usize) -> color_eyre::Result<BlockHash> {
+        self.blocks_indexes
+            .get(height)
+            .map(|record| record.header.block_hash())
+            .ok_or(eyre!("height not found"))
+    }
+
+    fn get_block(&self, height: usize) -> color_eyre::Result<Block> {
+        Ok(BitcoinDB::get_block(self, height)?)
+    }
+
+    fn get_transaction(&self, txid: &Txid) -> color_eyre::Result<Transaction> {
+        Ok(BitcoinDB::get_transaction(self, txid)?)
+    }
+
+    fn iter_block(&self, start: usize, end: usize) -> BlockIter {
+        BlockIter::from_range(self, start, end)
+    }
+}
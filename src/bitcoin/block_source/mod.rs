@@ -0,0 +1,6 @@
+mod _trait;
+mod local;
+mod rpc;
+
+pub use _trait::*;
+pub use rpc::*;
@@ -0,0 +1,242 @@
+use std::{fs, path::Path, str::FromStr, time::Duration};
+
+use bitcoin::{consensus::deserialize, hex::FromHex, Block, BlockHash, Transaction, Txid};
+use color_eyre::eyre::eyre;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::bitcoin::BlockIter;
+
+use super::BlockSource;
+
+#[derive(Debug, Clone)]
+pub enum RpcAuth {
+    None,
+    UserPass { user: String, password: String },
+}
+
+impl RpcAuth {
+    /// Reads the `.cookie` file that bitcoind writes in its datadir when no rpcuser/rpcpassword is set
+    pub fn from_cookie_file(path: &Path) -> color_eyre::Result<Self> {
+        let cookie = fs::read_to_string(path)?;
+
+        let (user, password) = cookie
+            .trim()
+            .split_once(':')
+            .ok_or(eyre!("Invalid cookie file: {path:?}"))?;
+
+        Ok(Self::UserPass {
+            user: user.to_owned(),
+            password: password.to_owned(),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<Value>,
+    error: Option<Value>,
+}
+
+///
+/// Fetches blocks from a Bitcoin Core node via its JSON-RPC interface.
+///
+/// Slower than reading blk files but works with remote nodes.
+///
+/// Spent outputs that the parser doesn't know are looked up with `getrawtransaction` without a block hash,
+/// which needs the node to run with `-txindex=1`, so pruned nodes can't be used.
+///
+#[derive(Debug, Clone)]
+pub struct RpcBlockSource {
+    url: String,
+    auth: RpcAuth,
+    client: Client,
+}
+
+impl RpcBlockSource {
+    pub fn new(url: &str, auth: RpcAuth) -> color_eyre::Result<Self> {
+        let source = Self {
+            url: url.to_owned(),
+            auth,
+            client: Client::builder().timeout(Duration::from_secs(60)).build()?,
+        };
+
+        source.check_txindex()?;
+
+        Ok(source)
+    }
+
+    /// Fails now instead of at the first unknown spent output, possibly hours into parsing
+    fn check_txindex(&self) -> color_eyre::Result<()> {
+        let index_info = self.call("getindexinfo", json!(["txindex"]))?;
+
+        if index_info.get("txindex").is_none() {
+            return Err(eyre!(
+                "The node at {} has no transaction index, restart it with -txindex=1 (which isn't possible on a pruned node)",
+                self.url
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn call(&self, method: &str, params: Value) -> color_eyre::Result<Value> {
+        let mut request = self.client.post(&self.url).json(&json!({
+            "jsonrpc": "1.0",
+            "id": "parser",
+            "method": method,
+            "params": params,
+        }));
+
+        if let RpcAuth::UserPass { user, password } = &self.auth {
+            request = request.basic_auth(user, Some(password));
+        }
+
+        let response = request.send()?.json::<RpcResponse>()?;
+
+        if let Some(error) = response.error.filter(|error| !error.is_null()) {
+            return Err(eyre!("RPC {method} failed: {error}"));
+        }
+
+        response
+            .result
+            .ok_or(eyre!("RPC {method} returned no result"))
+    }
+
+    fn call_hex(&self, method: &str, params: Value) -> color_eyre::Result<Vec<u8>> {
+        let result = self.call(method, params)?;
+
+        let hex = result
+            .as_str()
+            .ok_or(eyre!("RPC {method} didn't return a string"))?;
+
+        Ok(Vec::<u8>::from_hex(hex)?)
+    }
+}
+
+impl BlockSource for RpcBlockSource {
+    fn get_block_count(&self) -> color_eyre::Result<usize> {
+        let block_height = self
+            .call("getblockcount", json!([]))?
+            .as_u64()
+            .ok_or(eyre!("RPC getblockcount didn't return a number"))?;
+
+        Ok(block_height as usize + 1)
+    }
+
+    fn get_block_hash(&self, height: usize) -> color_eyre::Result<BlockHash> {
+        let hash = self.call("getblockhash", json!([height]))?;
+
+        let hash = hash
+            .as_str()
+            .ok_or(eyre!("RPC getblockhash didn't return a string"))?;
+
+        Ok(BlockHash::from_str(hash)?)
+    }
+
+    fn get_block(&self, height: usize) -> color_eyre::Result<Block> {
+        let hash = self.get_block_hash(height)?;
+
+        let bytes = self.call_hex("getblock", json!([hash.to_string(), 0]))?;
+
+        Ok(deserialize(&bytes)?)
+    }
+
+    fn get_transaction(&self, txid: &Txid) -> color_eyre::Result<Transaction> {
+        let bytes = self.call_hex("getrawtransaction", json!([txid.to_string(), false]))?;
+
+        Ok(deserialize(&bytes)?)
+    }
+
+    fn iter_block(&self, start: usize, end: usize) -> BlockIter {
+        BlockIter::from_range(self, start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use bitcoin::{blockdata::constants::genesis_block, consensus::encode::serialize_hex, Network};
+    use tiny_http::{Response, Server};
+
+    use super::*;
+
+    /// Serves the genesis block as if it were a node with a single block
+    fn spawn_mock_node() -> String {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+
+        thread::spawn(move || {
+            let genesis = genesis_block(Network::Bitcoin);
+
+            for mut request in server.incoming_requests() {
+                let body: Value = serde_json::from_reader(request.as_reader()).unwrap();
+
+                let result = match (body["method"].as_str().unwrap(), &body["params"]) {
+                    ("getindexinfo", _) => {
+                        Some(json!({ "txindex": { "synced": true, "best_block_height": 0 } }))
+                    }
+                    ("getblockcount", _) => Some(json!(0)),
+                    ("getblockhash", params) if params[0] == 0 => {
+                        Some(json!(genesis.block_hash().to_string()))
+                    }
+                    ("getblock", params) if params[0] == genesis.block_hash().to_string() => {
+                        Some(json!(serialize_hex(&genesis)))
+                    }
+                    ("getrawtransaction", params)
+                        if params[0] == genesis.txdata[0].txid().to_string() =>
+                    {
+                        Some(json!(serialize_hex(&genesis.txdata[0])))
+                    }
+                    _ => None,
+                };
+
+                let response = match result {
+                    Some(result) => json!({ "result": result, "error": null, "id": body["id"] }),
+                    None => json!({
+                        "result": null,
+                        "error": { "code": -8, "message": "not found" },
+                        "id": body["id"]
+                    }),
+                };
+
+                request
+                    .respond(Response::from_string(response.to_string()))
+                    .unwrap();
+            }
+        });
+
+        format!("http://127.0.0.1:{port}")
+    }
+
+    #[test]
+    fn test_rpc_block_source() {
+        let source = RpcBlockSource::new(&spawn_mock_node(), RpcAuth::None).unwrap();
+
+        let genesis = genesis_block(Network::Bitcoin);
+
+        assert_eq!(source.get_block_count().unwrap(), 1);
+
+        assert_eq!(
+            source.get_block(0).unwrap().block_hash(),
+            genesis.block_hash()
+        );
+
+        assert!(source.get_block(1).is_err());
+
+        assert_eq!(
+            source.get_transaction(&genesis.txdata[0].txid()).unwrap(),
+            genesis.txdata[0]
+        );
+
+        let blocks = source.iter_block(0, 2).collect::<Vec<_>>();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[0].as_ref().unwrap().block_hash(),
+            genesis.block_hash()
+        );
+        assert!(blocks[1].is_err());
+    }
+}
@@ -10,3 +10,5 @@ pub const TWO_WEEKS_IN_BLOCK_TIME: usize = 2 * ONE_WEEK_IN_BLOCK_TIME;
 pub const ONE_MONTH_IN_BLOCK_TIME: usize = 30 * ONE_DAY_IN_BLOCK_TIME;
 pub const THREE_MONTHS_IN_BLOCK_TIME: usize = 3 * ONE_MONTH_IN_BLOCK_TIME;
 pub const ONE_YEAR_IN_BLOCK_TIME: usize = 12 * ONE_MONTH_IN_BLOCK_TIME;
+
+pub const GENESIS_YEAR: u32 = 2009;
@@ -6,6 +6,12 @@ pub fn sats_to_btc(sats: u64) -> f32 {
     sats as f32 / SATOSHIS_PER_BITCOIN as f32
 }
 
+/// For amounts that get multiplied by prices and accumulated, where f32 drifts
+#[inline(always)]
+pub fn sats_to_btc_f64(sats: u64) -> f64 {
+    sats as f64 / SATOSHIS_PER_BITCOIN as f64
+}
+
 #[allow(unused)]
 #[inline(always)]
 pub fn btc_to_sats(btc: f32) -> u64 {
@@ -5,38 +5,38 @@
 use bitcoin::Block;
 use par_iter_sync::{IntoParallelIteratorSync, ParIterSync};
 
-use super::BitcoinDB;
+use crate::bitcoin::BlockSource;
 
-pub struct BlockIter(ParIterSync<Block>);
+/// Errors are yielded instead of ending the iteration, which would be mistaken for the tip of the chain
+pub struct BlockIter(ParIterSync<color_eyre::Result<Block>>);
 
 impl BlockIter {
     /// the worker threads are dispatched in this `new` constructor!
-    pub fn new<T>(db: &BitcoinDB, heights: T) -> Self
+    pub fn new<S, T>(source: &S, heights: T) -> Self
     where
+        S: BlockSource + Clone + 'static,
         T: IntoIterator<Item = usize> + Send + 'static,
         <T as IntoIterator>::IntoIter: Send + 'static,
     {
-        let db_ref = db.clone();
-        BlockIter(
-            heights.into_par_iter_sync(move |h| match db_ref.get_block(h) {
-                Ok(blk) => Ok(blk),
-                Err(_) => Err(()),
-            }),
-        )
+        let source_ref = source.clone();
+        BlockIter(heights.into_par_iter_sync(move |h| Ok::<_, ()>(source_ref.get_block(h))))
     }
 
     /// the worker threads are dispatched in this `new` constructor!
-    pub fn from_range(db: &BitcoinDB, start: usize, end: usize) -> Self {
+    pub fn from_range<S>(source: &S, start: usize, end: usize) -> Self
+    where
+        S: BlockSource + Clone + 'static,
+    {
         if end <= start {
-            BlockIter::new(db, Vec::new())
+            BlockIter::new(source, Vec::new())
         } else {
-            BlockIter::new(db, start..end)
+            BlockIter::new(source, start..end)
         }
     }
 }
 
 impl Iterator for BlockIter {
-    type Item = Block;
+    type Item = color_eyre::Result<Block>;
 
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next()
@@ -29,12 +29,14 @@ mod block_iter;
 mod blocks_indexes;
 mod errors;
 mod reader;
+mod snapshot;
 mod txdb;
 
 use blk_files::*;
 use blocks_indexes::*;
 use errors::*;
 use reader::*;
+use snapshot::*;
 use txdb::*;
 
 use std::ops::Deref;
@@ -97,16 +99,59 @@ impl BitcoinDB {
         }
         let blk_path = p.join("blocks");
         let index_path = blk_path.join("index");
-        let blocks_indexes = BlocksIndexes::new(index_path.as_path())?;
-        let tx_db = if tx_index {
-            let tx_index_path = p.join("indexes").join("txindex");
-            TxDB::new(&tx_index_path)
+        let tx_index_path = p.join("indexes").join("txindex");
+        Self::open(
+            &blk_path,
+            &index_path,
+            tx_index.then_some(tx_index_path.as_path()),
+        )
+    }
+
+    ///
+    /// Same as `new` but reads the block index and the txindex from a copy stored in `snapshot_path`.
+    ///
+    /// Since bitcoind only locks its LevelDBs, it can keep running (and syncing) in the meantime.
+    /// The copy is refreshed on each call, only new table files are copied.
+    ///
+    pub fn new_from_snapshot(
+        p: &Path,
+        snapshot_path: &Path,
+        tx_index: bool,
+    ) -> OpResult<BitcoinDB> {
+        if !p.exists() {
+            return Err(OpError::from("data_dir does not exist"));
+        }
+        let blk_path = p.join("blocks");
+
+        let index_path = snapshot_path.join("index");
+        snapshot_leveldb(&blk_path.join("index"), &index_path)?;
+
+        let tx_index_path = snapshot_path.join("txindex");
+        if tx_index {
+            snapshot_leveldb(&p.join("indexes").join("txindex"), &tx_index_path)?;
+        }
+
+        Self::open(
+            &blk_path,
+            &index_path,
+            tx_index.then_some(tx_index_path.as_path()),
+        )
+    }
+
+    fn open(
+        blk_path: &Path,
+        index_path: &Path,
+        tx_index_path: Option<&Path>,
+    ) -> OpResult<BitcoinDB> {
+        let blocks_indexes = BlocksIndexes::new(index_path)?;
+        let tx_db = if let Some(tx_index_path) = tx_index_path {
+            TxDB::new(tx_index_path)
         } else {
             TxDB::null()
         };
         let inner = InnerDB {
             blocks_indexes,
-            blk_files: BlkFiles::new(blk_path.as_path())?,
+            blk_files: BlkFiles::new(blk_path)?,
             tx_db,
         };
         Ok(BitcoinDB(Arc::new(inner)))
@@ -258,16 +303,4 @@ impl BitcoinDB {
     pub fn iter_block(&self, start: usize, end: usize) -> BlockIter {
         BlockIter::from_range(self, start, end)
     }
-
-    pub fn check_if_txout_value_is_zero(&self, txid: &Txid, vout: u32) -> bool {
-        self.get_transaction(txid)
-            .unwrap()
-            .output
-            .get(vout as usize)
-            .unwrap()
-            .to_owned()
-            .value
-            .to_sat()
-            == 0
-    }
 }
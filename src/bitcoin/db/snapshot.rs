@@ -0,0 +1,182 @@
+//!
+//! Copies of the LevelDB folders that bitcoind keeps locked while running.
+//!
+//! Table files (`.ldb`/`.sst`) are immutable once written, so they're only copied when missing or
+//! modified after their copy, which keeps subsequent snapshots cheap. Everything else (`CURRENT`, `MANIFEST-*`, `*.log`)
+//! is copied every time and before listing the tables, so that every table it references gets copied.
+//!
+//! Files of the copy that aren't in the source anymore are removed, including the ones written when opening the copy.
+//!
+//! If bitcoind compacts the database while copying, a referenced table can disappear before
+//! being copied, in which case the snapshot is simply retried.
+//!
+
+use std::{
+    collections::BTreeSet,
+    fs, io,
+    path::{Path, PathBuf},
+    thread::sleep,
+    time::Duration,
+};
+
+use super::{OpError, OpErrorKind, OpResult};
+
+const MAX_ATTEMPTS: usize = 5;
+
+pub fn snapshot_leveldb(src: &Path, dst: &Path) -> OpResult<()> {
+    if !src.exists() {
+        return Err(OpError::from("leveldb to snapshot does not exist"));
+    }
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        match try_snapshot_leveldb(src, dst) {
+            Err(OpError {
+                kind: OpErrorKind::IoError(error),
+                ..
+            }) if error.kind() == io::ErrorKind::NotFound && attempt < MAX_ATTEMPTS => {
+                println!("{src:?} changed while taking a snapshot, retrying...");
+                sleep(Duration::from_millis(500));
+            }
+            result => return result,
+        }
+    }
+
+    unreachable!()
+}
+
+fn try_snapshot_leveldb(src: &Path, dst: &Path) -> OpResult<()> {
+    fs::create_dir_all(dst)?;
+
+    let mut others = list_files(src)?
+        .into_iter()
+        .filter(|path| file_name(path) != "LOCK" && !is_table(path))
+        .collect::<Vec<_>>();
+
+    // Manifest and current first, logs last to get as many writes as possible
+    others.sort_by_key(|path| file_name(path).ends_with(".log"));
+
+    others.iter().try_for_each(|path| -> OpResult<()> {
+        fs::copy(path, dst.join(file_name(path)))?;
+        Ok(())
+    })?;
+
+    // Listed after the manifest was copied to get every table it references
+    let tables = list_files(src)?
+        .into_iter()
+        .filter(|path| is_table(path))
+        .collect::<BTreeSet<_>>();
+
+    // Files that bitcoind removed since the last snapshot are now useless
+    list_files(dst)?
+        .into_iter()
+        .filter(|path| {
+            let src_path = src.join(file_name(path));
+            !tables.contains(&src_path) && !others.contains(&src_path)
+        })
+        .try_for_each(fs::remove_file)?;
+
+    tables.iter().try_for_each(|path| -> OpResult<()> {
+        let dst_path = dst.join(file_name(path));
+
+        // The copy is made after the source was last modified, unless the source was rewritten since
+        let is_up_to_date = fs::metadata(&dst_path).ok().is_some_and(|dst_metadata| {
+            fs::metadata(path).is_ok_and(|src_metadata| {
+                src_metadata.len() == dst_metadata.len()
+                    && src_metadata
+                        .modified()
+                        .ok()
+                        .zip(dst_metadata.modified().ok())
+                        .is_some_and(|(src_modified, dst_modified)| src_modified <= dst_modified)
+            })
+        });
+
+        if !is_up_to_date {
+            fs::copy(path, dst_path)?;
+        }
+
+        Ok(())
+    })
+}
+
+fn list_files(path: &Path) -> OpResult<BTreeSet<PathBuf>> {
+    Ok(fs::read_dir(path)?
+        .flat_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect())
+}
+
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+}
+
+fn is_table(path: &Path) -> bool {
+    let name = file_name(path);
+    name.ends_with(".ldb") || name.ends_with(".sst")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs::File,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_snapshot_leveldb() {
+        let root = std::env::temp_dir().join(format!(
+            "snapshot_leveldb_{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let src = root.join("src");
+        let dst = root.join("dst");
+
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("CURRENT"), "MANIFEST-000001\n").unwrap();
+        fs::write(src.join("MANIFEST-000001"), "manifest").unwrap();
+        fs::write(src.join("000002.ldb"), "table a").unwrap();
+        fs::write(src.join("LOCK"), "").unwrap();
+
+        snapshot_leveldb(&src, &dst).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dst.join("000002.ldb")).unwrap(),
+            "table a"
+        );
+        assert!(!dst.join("LOCK").exists());
+
+        // Rewritten with the same name and size after the copy
+        fs::write(src.join("000002.ldb"), "table b").unwrap();
+        File::options()
+            .write(true)
+            .open(src.join("000002.ldb"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+
+        // Compacted into a new manifest
+        fs::rename(src.join("MANIFEST-000001"), src.join("MANIFEST-000003")).unwrap();
+        fs::write(src.join("CURRENT"), "MANIFEST-000003\n").unwrap();
+
+        // Written when opening the copy
+        fs::write(dst.join("000004.log"), "log").unwrap();
+
+        snapshot_leveldb(&src, &dst).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dst.join("000002.ldb")).unwrap(),
+            "table b"
+        );
+        assert!(dst.join("MANIFEST-000003").exists());
+        assert!(!dst.join("MANIFEST-000001").exists());
+        assert!(!dst.join("000004.log").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,5 +1,11 @@
-use super::NUMBER_OF_UNSAFE_BLOCKS;
+use crate::config::Config;
+
+use super::BLOCKS_PER_HAVLING_EPOCH;
 
 pub fn check_if_height_safe(height: usize, block_count: usize) -> bool {
-    height < block_count - NUMBER_OF_UNSAFE_BLOCKS
+    height < block_count.saturating_sub(Config::get().unsafe_blocks)
+}
+
+pub fn height_to_epoch(height: usize) -> u32 {
+    (height / BLOCKS_PER_HAVLING_EPOCH) as u32
 }
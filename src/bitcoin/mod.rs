@@ -1,4 +1,5 @@
 mod addresses;
+mod block_source;
 mod consts;
 mod converters;
 mod daemon;
@@ -6,6 +7,7 @@ mod db;
 mod height;
 
 pub use addresses::*;
+pub use block_source::*;
 pub use consts::*;
 pub use converters::*;
 pub use daemon::*;
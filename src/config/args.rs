@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+
+use clap::ArgAction;
+use serde::Deserialize;
+
+use super::{
+    CsvLayout, ExportCadence, PriceSourceKind, StatesSnapshotCadence, UTXOCohortDefinition,
+};
+
+/// Every option can be set (from highest to lowest priority) via a flag, an environment variable or the TOML config file
+#[derive(clap::Args, Deserialize, Debug, Default, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Args {
+    /// Path to the TOML config file (default: ./config.toml if it exists)
+    #[arg(long, env = "SATONOMICS_CONFIG")]
+    #[serde(skip)]
+    pub config: Option<String>,
+
+    /// Bitcoin Core's datadir (default: ~/.bitcoin)
+    #[arg(long, env = "SATONOMICS_BITCOIN_DATADIR")]
+    pub bitcoin_datadir: Option<String>,
+
+    /// Fetch blocks via bitcoind's JSON-RPC instead of reading blk files, works with remote nodes running with -txindex=1
+    #[arg(long, env = "SATONOMICS_RPC_URL")]
+    pub rpc_url: Option<String>,
+
+    /// RPC user (default: read from the .cookie file in the datadir)
+    #[arg(long, env = "SATONOMICS_RPC_USER")]
+    pub rpc_user: Option<String>,
+
+    /// RPC password
+    #[arg(long, env = "SATONOMICS_RPC_PASSWORD")]
+    pub rpc_password: Option<String>,
+
+    /// Where the datasets are exported (default: ./datasets)
+    #[arg(long, env = "SATONOMICS_DATASETS_PATH")]
+    pub datasets_path: Option<String>,
+
+    /// Where the prices are cached (default: ./price)
+    #[arg(long, env = "SATONOMICS_PRICE_PATH")]
+    pub price_path: Option<String>,
+
+    /// Where the states and databases are stored (default: ./target/outputs)
+    #[arg(long, env = "SATONOMICS_OUTPUTS_PATH")]
+    pub outputs_path: Option<String>,
+
+    /// Where manually downloaded files (like binance.har) are read from (default: ./imports)
+    #[arg(long, env = "SATONOMICS_IMPORTS_PATH")]
+    pub imports_path: Option<String>,
+
+    /// Read bitcoind's block index and txindex from a copy instead of stopping the node (default: false)
+    #[arg(long, env = "SATONOMICS_SNAPSHOT", action = ArgAction::Set)]
+    pub snapshot: Option<bool>,
+
+    /// Parse blocks and insert the results in the datasets (default: true)
+    #[arg(long, env = "SATONOMICS_INSERT", action = ArgAction::Set)]
+    pub insert: Option<bool>,
+
+    /// Export datasets, databases and states to disk (default: true)
+    #[arg(long, env = "SATONOMICS_EXPORT", action = ArgAction::Set)]
+    pub export: Option<bool>,
+
+    /// How much is parsed between two exports (default: month)
+    #[arg(long, env = "SATONOMICS_EXPORT_CADENCE", value_enum)]
+    pub export_cadence: Option<ExportCadence>,
+
+    /// Number of blocks from the tip considered unsafe because of possible reorgs (default: 100)
+    #[arg(long, env = "SATONOMICS_UNSAFE_BLOCKS")]
+    pub unsafe_blocks: Option<usize>,
+
+    /// Compute address cohorts, and the datasets depending on them (mining, transaction, cointime) (default: true)
+    #[arg(long, env = "SATONOMICS_COMPUTE_ADDRESSES", action = ArgAction::Set)]
+    pub compute_addresses: Option<bool>,
+
+    /// Also compute a UTXO cohort per halving epoch of creation, like the ones per year (default: false)
+    #[arg(long, env = "SATONOMICS_EPOCH_COHORTS", action = ArgAction::Set)]
+    pub epoch_cohorts: Option<bool>,
+
+    /// Age in days under which outputs belong to the short term holders, and from which to the long term ones (default: 155)
+    #[arg(long, env = "SATONOMICS_STH_DAYS")]
+    pub sth_days: Option<u32>,
+
+    /// More UTXO cohorts, only from the config file as `[[utxo_cohorts]]` tables (default: none)
+    #[arg(skip)]
+    pub utxo_cohorts: Option<Vec<UTXOCohortDefinition>>,
+
+    /// Keep a copy of the states and databases every epoch, month, year or number of blocks, to replay from (default: none)
+    #[arg(long, env = "SATONOMICS_STATES_SNAPSHOTS")]
+    pub states_snapshots: Option<StatesSnapshotCadence>,
+
+    /// Address the API server listens on (default: 127.0.0.1:3110)
+    #[arg(long, env = "SATONOMICS_API_ADDRESS")]
+    pub api_address: Option<String>,
+
+    /// Number of states snapshots kept, the oldest being removed first (default: 3)
+    #[arg(long, env = "SATONOMICS_STATES_SNAPSHOTS_KEPT")]
+    pub states_snapshots_kept: Option<usize>,
+
+    /// Also export every height and date map as Parquet tables in this folder (default: none)
+    #[arg(long, env = "SATONOMICS_PARQUET_PATH")]
+    pub parquet_path: Option<String>,
+
+    /// Also export every map as CSV in the datasets folder, unless a map sets its own layout (default: none)
+    #[arg(long, env = "SATONOMICS_CSV", value_enum)]
+    pub csv: Option<CsvLayout>,
+
+    /// Layout of specific maps in the CSV export, by path relative to the datasets folder and without the /height or /date suffix, only from the config file as a `[csv_maps]` table (default: none)
+    #[arg(skip)]
+    pub csv_maps: Option<BTreeMap<String, CsvLayout>>,
+
+    /// Compress binary chunks and states with zstd at this level (1 to 22), uncompressed files still load (default: none)
+    #[arg(long, env = "SATONOMICS_ZSTD_LEVEL")]
+    pub zstd_level: Option<i32>,
+
+    /// Where the prices of new blocks are looked for, in order (default: file,kraken,binance,har)
+    #[arg(
+        long,
+        env = "SATONOMICS_PRICE_SOURCES",
+        value_enum,
+        value_delimiter = ','
+    )]
+    pub price_sources: Option<Vec<PriceSourceKind>>,
+
+    /// Also denominate every map in dollars in these currencies, using the daily rates of imports/currencies/{currency}.csv (default: none)
+    #[arg(long, env = "SATONOMICS_CURRENCIES", value_delimiter = ',')]
+    pub currencies: Option<Vec<String>>,
+
+    /// Quarantine a fetched price differing from another source by more than this percentage, 0 to disable (default: 5)
+    #[arg(long, env = "SATONOMICS_PRICE_MAX_DEVIATION")]
+    pub price_max_deviation: Option<f32>,
+
+    /// Quarantine a fetched price differing from the one of the previous block by more than this percentage, unless another source agrees, 0 to disable (default: 20)
+    #[arg(long, env = "SATONOMICS_PRICE_MAX_GAP")]
+    pub price_max_gap: Option<f32>,
+
+    /// Export the supply of every cohort by acquisition price each date, in log buckets, this many per power of ten of the price (default: none)
+    #[arg(long, env = "SATONOMICS_URPD_BUCKETS_PER_DECADE")]
+    pub urpd_buckets_per_decade: Option<u32>,
+}
+
+impl Args {
+    /// Values already in `self` take precedence over the ones in `other`
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            config: self.config.or(other.config),
+            bitcoin_datadir: self.bitcoin_datadir.or(other.bitcoin_datadir),
+            rpc_url: self.rpc_url.or(other.rpc_url),
+            rpc_user: self.rpc_user.or(other.rpc_user),
+            rpc_password: self.rpc_password.or(other.rpc_password),
+            datasets_path: self.datasets_path.or(other.datasets_path),
+            price_path: self.price_path.or(other.price_path),
+            outputs_path: self.outputs_path.or(other.outputs_path),
+            imports_path: self.imports_path.or(other.imports_path),
+            snapshot: self.snapshot.or(other.snapshot),
+            insert: self.insert.or(other.insert),
+            export: self.export.or(other.export),
+            export_cadence: self.export_cadence.or(other.export_cadence),
+            unsafe_blocks: self.unsafe_blocks.or(other.unsafe_blocks),
+            compute_addresses: self.compute_addresses.or(other.compute_addresses),
+            epoch_cohorts: self.epoch_cohorts.or(other.epoch_cohorts),
+            sth_days: self.sth_days.or(other.sth_days),
+            utxo_cohorts: self.utxo_cohorts.or(other.utxo_cohorts),
+            states_snapshots: self.states_snapshots.or(other.states_snapshots),
+            states_snapshots_kept: self.states_snapshots_kept.or(other.states_snapshots_kept),
+            api_address: self.api_address.or(other.api_address),
+            parquet_path: self.parquet_path.or(other.parquet_path),
+            csv: self.csv.or(other.csv),
+            csv_maps: self.csv_maps.or(other.csv_maps),
+            zstd_level: self.zstd_level.or(other.zstd_level),
+            price_sources: self.price_sources.or(other.price_sources),
+            currencies: self.currencies.or(other.currencies),
+            price_max_deviation: self.price_max_deviation.or(other.price_max_deviation),
+            price_max_gap: self.price_max_gap.or(other.price_max_gap),
+            urpd_buckets_per_decade: self
+                .urpd_buckets_per_decade
+                .or(other.urpd_buckets_per_decade),
+        }
+    }
+}
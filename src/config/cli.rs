@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+
+use super::Args;
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(flatten)]
+    pub args: Args,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Parse once up to the current tip and exit, bitcoind needs to be stopped beforehand unless using --snapshot
+    Parse,
+
+    /// Stop bitcoind (unless using --snapshot), parse up to the tip, restart it, wait for a new block and repeat (default)
+    Watch,
+
+    /// Delete the selected outputs, prices are never removed
+    Reset {
+        #[arg(long)]
+        states: bool,
+
+        #[arg(long)]
+        databases: bool,
+
+        #[arg(long)]
+        datasets: bool,
+    },
+
+    /// Print the value of a map at a given height and/or date
+    Inspect {
+        /// Path of the map as found in paths.json, with or without the /height or /date suffix
+        path: String,
+
+        #[arg(long)]
+        height: Option<usize>,
+
+        #[arg(long)]
+        date: Option<NaiveDate>,
+    },
+
+    /// Check that states, databases and datasets are in sync, fails if the next parse would start over
+    Verify,
+
+    /// Import 1 minute candles from CSV dumps of exchanges (timestamp,open,high,low,close,volume,...) into the price folder
+    ImportCandles { paths: Vec<String> },
+}
@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CsvLayout {
+    /// A single table of `path,height,value` (or `path,date,value`) rows
+    Long,
+    /// A table per dataset with a column per map
+    Wide,
+}
@@ -0,0 +1,35 @@
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportCadence {
+    Day,
+    Week,
+    #[default]
+    Month,
+    Year,
+}
+
+impl ExportCadence {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Year => "year",
+        }
+    }
+
+    pub fn is_new_period(&self, current_date: NaiveDate, next_date: NaiveDate) -> bool {
+        match self {
+            Self::Day => current_date != next_date,
+            Self::Week => current_date.iso_week() != next_date.iso_week(),
+            Self::Month => {
+                current_date.year() != next_date.year() || current_date.month() != next_date.month()
+            }
+            Self::Year => current_date.year() != next_date.year(),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+mod args;
+mod cli;
+mod csv_layout;
+mod export_cadence;
+mod price_source_kind;
+mod settings;
+mod states_snapshot_cadence;
+mod utxo_cohort_definition;
+
+pub use args::*;
+pub use cli::*;
+pub use csv_layout::*;
+pub use export_cadence::*;
+pub use price_source_kind::*;
+pub use settings::*;
+pub use states_snapshot_cadence::*;
+pub use utxo_cohort_definition::*;
@@ -0,0 +1,21 @@
+use clap::ValueEnum;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSourceKind {
+    /// Candles of `prices.csv` or `prices.json` in the imports folder
+    File,
+    /// Last 720 minutes and days from Kraken's API
+    Kraken,
+    /// Last 1000 minutes from Binance's API
+    Binance,
+    /// Minutes from `binance.har` in the imports folder, exported from the browser on Binance's chart
+    Har,
+}
+
+impl PriceSourceKind {
+    pub fn default_order() -> Vec<Self> {
+        vec![Self::File, Self::Kraken, Self::Binance, Self::Har]
+    }
+}
@@ -0,0 +1,245 @@
+use std::{collections::BTreeMap, env, fs, path::Path, sync::OnceLock};
+
+use color_eyre::eyre::eyre;
+
+use crate::bitcoin::{RpcAuth, NUMBER_OF_UNSAFE_BLOCKS};
+
+use super::{
+    Args, CsvLayout, ExportCadence, PriceSourceKind, StatesSnapshotCadence, UTXOCohortDefinition,
+};
+
+const DEFAULT_CONFIG_PATH: &str = "./config.toml";
+const DEFAULT_DATASETS_PATH: &str = "./datasets";
+const DEFAULT_PRICE_PATH: &str = "./price";
+const DEFAULT_OUTPUTS_PATH: &str = "./target/outputs";
+const DEFAULT_IMPORTS_PATH: &str = "./imports";
+const DEFAULT_STATES_SNAPSHOTS_KEPT: usize = 3;
+const DEFAULT_API_ADDRESS: &str = "127.0.0.1:3110";
+const DEFAULT_PRICE_MAX_DEVIATION: f32 = 5.0;
+const DEFAULT_PRICE_MAX_GAP: f32 = 20.0;
+const DEFAULT_STH_DAYS: u32 = 155;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub bitcoin_datadir: String,
+    pub rpc_url: Option<String>,
+    pub rpc_user: Option<String>,
+    pub rpc_password: Option<String>,
+    pub datasets_path: String,
+    pub price_path: String,
+    pub outputs_path: String,
+    pub imports_path: String,
+    pub snapshot: bool,
+    pub insert: bool,
+    pub export: bool,
+    pub export_cadence: ExportCadence,
+    pub unsafe_blocks: usize,
+    pub compute_addresses: bool,
+    pub epoch_cohorts: bool,
+    pub sth_days: u32,
+    pub utxo_cohorts: Vec<UTXOCohortDefinition>,
+    pub states_snapshots: Option<StatesSnapshotCadence>,
+    pub states_snapshots_kept: usize,
+    pub api_address: String,
+    pub parquet_path: Option<String>,
+    pub csv: Option<CsvLayout>,
+    pub csv_maps: BTreeMap<String, CsvLayout>,
+    pub zstd_level: Option<i32>,
+    pub price_sources: Vec<PriceSourceKind>,
+    pub currencies: Vec<String>,
+    /// In percent, 0 when disabled
+    pub price_max_deviation: f32,
+    /// In percent, 0 when disabled
+    pub price_max_gap: f32,
+    pub urpd_buckets_per_decade: Option<u32>,
+}
+
+impl Config {
+    pub fn from_args(args: Args) -> color_eyre::Result<Self> {
+        let config_path = args.config.clone().or_else(|| {
+            Path::new(DEFAULT_CONFIG_PATH)
+                .exists()
+                .then(|| DEFAULT_CONFIG_PATH.to_owned())
+        });
+
+        let args = if let Some(config_path) = config_path {
+            let file_args = toml::from_str::<Args>(&fs::read_to_string(&config_path)?)
+                .map_err(|error| eyre!("Failed to parse {config_path}: {error}"))?;
+
+            args.merge(file_args)
+        } else {
+            args
+        };
+
+        let unsafe_blocks = args.unsafe_blocks.unwrap_or(NUMBER_OF_UNSAFE_BLOCKS);
+
+        if unsafe_blocks == 0 {
+            return Err(eyre!("unsafe_blocks needs to be at least 1"));
+        }
+
+        if args
+            .zstd_level
+            .is_some_and(|level| !(1..=22).contains(&level))
+        {
+            return Err(eyre!("zstd_level needs to be between 1 and 22"));
+        }
+
+        if args
+            .price_sources
+            .as_ref()
+            .is_some_and(|sources| sources.is_empty())
+        {
+            return Err(eyre!("price_sources needs at least one source"));
+        }
+
+        if [args.price_max_deviation, args.price_max_gap]
+            .iter()
+            .flatten()
+            .any(|percentage| !percentage.is_finite() || *percentage < 0.0)
+        {
+            return Err(eyre!(
+                "price_max_deviation and price_max_gap need to be positive percentages"
+            ));
+        }
+
+        if args.sth_days == Some(0) {
+            return Err(eyre!("sth_days needs to be at least 1"));
+        }
+
+        if args.urpd_buckets_per_decade == Some(0) {
+            return Err(eyre!("urpd_buckets_per_decade needs to be at least 1"));
+        }
+
+        let utxo_cohorts = args.utxo_cohorts.clone().unwrap_or_default();
+
+        utxo_cohorts
+            .iter()
+            .try_for_each(UTXOCohortDefinition::validate)?;
+
+        if let Some((_, cohort)) = utxo_cohorts.iter().enumerate().find(|(index, cohort)| {
+            utxo_cohorts[..*index]
+                .iter()
+                .any(|other| other.name == cohort.name)
+        }) {
+            return Err(eyre!(
+                "There are multiple UTXO cohorts named \"{}\"",
+                cohort.name
+            ));
+        }
+
+        // Used in paths
+        if let Some(currency) = args.currencies.iter().flatten().find(|currency| {
+            currency.is_empty()
+                || !currency
+                    .chars()
+                    .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit())
+        }) {
+            return Err(eyre!(
+                "Currency \"{currency}\" needs to be made of lowercase letters and digits"
+            ));
+        }
+
+        Ok(Self {
+            bitcoin_datadir: args.bitcoin_datadir.unwrap_or_else(default_bitcoin_datadir),
+            rpc_url: args.rpc_url,
+            rpc_user: args.rpc_user,
+            rpc_password: args.rpc_password,
+            datasets_path: args
+                .datasets_path
+                .unwrap_or_else(|| DEFAULT_DATASETS_PATH.to_owned()),
+            price_path: args
+                .price_path
+                .unwrap_or_else(|| DEFAULT_PRICE_PATH.to_owned()),
+            outputs_path: args
+                .outputs_path
+                .unwrap_or_else(|| DEFAULT_OUTPUTS_PATH.to_owned()),
+            imports_path: args
+                .imports_path
+                .unwrap_or_else(|| DEFAULT_IMPORTS_PATH.to_owned()),
+            snapshot: args.snapshot.unwrap_or(false),
+            insert: args.insert.unwrap_or(true),
+            export: args.export.unwrap_or(true),
+            export_cadence: args.export_cadence.unwrap_or_default(),
+            unsafe_blocks,
+            compute_addresses: args.compute_addresses.unwrap_or(true),
+            epoch_cohorts: args.epoch_cohorts.unwrap_or(false),
+            sth_days: args.sth_days.unwrap_or(DEFAULT_STH_DAYS),
+            utxo_cohorts,
+            states_snapshots: args.states_snapshots,
+            states_snapshots_kept: args
+                .states_snapshots_kept
+                .unwrap_or(DEFAULT_STATES_SNAPSHOTS_KEPT),
+            api_address: args
+                .api_address
+                .unwrap_or_else(|| DEFAULT_API_ADDRESS.to_owned()),
+            parquet_path: args.parquet_path,
+            csv: args.csv,
+            csv_maps: args.csv_maps.unwrap_or_default(),
+            zstd_level: args.zstd_level,
+            price_sources: args
+                .price_sources
+                .unwrap_or_else(PriceSourceKind::default_order),
+            currencies: args.currencies.unwrap_or_default(),
+            price_max_deviation: args
+                .price_max_deviation
+                .unwrap_or(DEFAULT_PRICE_MAX_DEVIATION),
+            price_max_gap: args.price_max_gap.unwrap_or(DEFAULT_PRICE_MAX_GAP),
+            urpd_buckets_per_decade: args.urpd_buckets_per_decade,
+        })
+    }
+
+    pub fn rpc_auth(&self) -> color_eyre::Result<RpcAuth> {
+        if let Some(user) = self.rpc_user.as_ref() {
+            Ok(RpcAuth::UserPass {
+                user: user.to_owned(),
+                password: self.rpc_password.clone().unwrap_or_default(),
+            })
+        } else {
+            RpcAuth::from_cookie_file(&Path::new(&self.bitcoin_datadir).join(".cookie"))
+        }
+    }
+
+    /// Layout of a map in the CSV export, its own or the global one, if any
+    pub fn csv_layout(&self, name: &str) -> Option<CsvLayout> {
+        self.csv_maps.get(name).copied().or(self.csv)
+    }
+
+    /// Reading blk files directly requires bitcoind to release its locks, unless reading from a snapshot
+    pub fn needs_node_stopped(&self) -> bool {
+        self.rpc_url.is_none() && !self.snapshot
+    }
+
+    /// The age of outputs in blocks is only tracked if a UTXO cohort needs it
+    pub fn needs_blocks_old(&self) -> bool {
+        self.utxo_cohorts
+            .iter()
+            .any(UTXOCohortDefinition::uses_blocks)
+    }
+
+    pub fn snapshot_path(&self) -> String {
+        format!("{}/snapshot", self.outputs_path)
+    }
+
+    /// Makes the config available globally via `Config::get`, can only be done once
+    pub fn init(self) -> &'static Self {
+        if CONFIG.set(self).is_err() {
+            panic!("Config was already initialized");
+        }
+
+        Self::get()
+    }
+
+    pub fn get() -> &'static Self {
+        CONFIG
+            .get()
+            .expect("Config was not initialized, `Config::init` needs to be called first")
+    }
+}
+
+fn default_bitcoin_datadir() -> String {
+    let home = env::var("HOME").unwrap_or_else(|_| ".".to_owned());
+
+    format!("{home}/.bitcoin")
+}
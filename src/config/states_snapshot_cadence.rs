@@ -0,0 +1,69 @@
+use std::{fmt, str::FromStr};
+
+use chrono::Datelike;
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+
+use crate::{bitcoin::BLOCKS_PER_HAVLING_EPOCH, io::Commit};
+
+/// Either `epoch`, `month`, `year` or a number of blocks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum StatesSnapshotCadence {
+    Epoch,
+    Month,
+    Year,
+    Blocks(usize),
+}
+
+impl StatesSnapshotCadence {
+    pub fn is_new_period(&self, last: &Commit, next: &Commit) -> bool {
+        match self {
+            Self::Epoch => {
+                last.height / BLOCKS_PER_HAVLING_EPOCH != next.height / BLOCKS_PER_HAVLING_EPOCH
+            }
+            Self::Month => {
+                last.date.year() != next.date.year() || last.date.month() != next.date.month()
+            }
+            Self::Year => last.date.year() != next.date.year(),
+            Self::Blocks(blocks) => last.height / blocks != next.height / blocks,
+        }
+    }
+}
+
+impl FromStr for StatesSnapshotCadence {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "epoch" => Ok(Self::Epoch),
+            "month" => Ok(Self::Month),
+            "year" => Ok(Self::Year),
+            _ => match s.parse::<usize>() {
+                Ok(0) | Err(_) => Err(eyre!(
+                    "Expected epoch, month, year or a number of blocks, got \"{s}\""
+                )),
+                Ok(blocks) => Ok(Self::Blocks(blocks)),
+            },
+        }
+    }
+}
+
+impl TryFrom<String> for StatesSnapshotCadence {
+    type Error = color_eyre::Report;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for StatesSnapshotCadence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Epoch => write!(f, "epoch"),
+            Self::Month => write!(f, "month"),
+            Self::Year => write!(f, "year"),
+            Self::Blocks(blocks) => write!(f, "{blocks}"),
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+///
+/// UTXO cohort defined in the config file, made of the outputs within every bound that is set, like:
+///
+/// ```toml
+/// [[utxo_cohorts]]
+/// name = "from155dto6m"
+/// from_days = 155
+/// to_days = 180
+/// ```
+///
+/// Ages go from a bound included to a bound excluded, years are both included.
+///
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct UTXOCohortDefinition {
+    /// Its datasets are in `{datasets_path}/custom/{name}`, underscores aren't allowed since they're folder separators in paths
+    pub name: String,
+    pub from_days: Option<u32>,
+    pub to_days: Option<u32>,
+    pub from_blocks: Option<u32>,
+    pub to_blocks: Option<u32>,
+    pub from_year: Option<u32>,
+    pub to_year: Option<u32>,
+}
+
+impl UTXOCohortDefinition {
+    pub fn uses_blocks(&self) -> bool {
+        self.from_blocks.is_some() || self.to_blocks.is_some()
+    }
+
+    pub fn validate(&self) -> color_eyre::Result<()> {
+        let name = &self.name;
+
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|char| char.is_ascii_lowercase() || char.is_ascii_digit())
+        {
+            return Err(eyre!(
+                "The name of the UTXO cohort \"{name}\" needs to be made of lowercase letters and digits"
+            ));
+        }
+
+        let bounds = [
+            (self.from_days, self.to_days),
+            (self.from_blocks, self.to_blocks),
+        ];
+
+        if bounds
+            .iter()
+            .chain([&(self.from_year, self.to_year)])
+            .all(|(from, to)| from.is_none() && to.is_none())
+        {
+            return Err(eyre!("The UTXO cohort \"{name}\" needs at least one bound"));
+        }
+
+        if bounds
+            .iter()
+            .any(|bounds| matches!(bounds, (Some(from), Some(to)) if from >= to))
+            || matches!((self.from_year, self.to_year), (Some(from), Some(to)) if from > to)
+        {
+            return Err(eyre!(
+                "The UTXO cohort \"{name}\" has a bound after its end"
+            ));
+        }
+
+        Ok(())
+    }
+}
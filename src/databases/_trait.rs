@@ -8,6 +8,9 @@ where
 {
     fn import() -> Self;
 
+    /// Writes everything that `export` will apply once the checkpoint at `height` is committed
+    fn stage(&self, height: usize) -> color_eyre::Result<()>;
+
     fn export(&mut self) -> color_eyre::Result<()>;
 
     fn folder<'a>() -> &'a str;
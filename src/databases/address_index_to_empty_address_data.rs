@@ -83,14 +83,18 @@ impl AnyDatabaseGroup for AddressIndexToEmptyAddressData {
         }
     }
 
+    fn stage(&self, height: usize) -> color_eyre::Result<()> {
+        self.map
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+
+        self.metadata.stage(height)
+    }
+
     fn export(&mut self) -> color_eyre::Result<()> {
         mem::take(&mut self.map)
             .into_par_iter()
-            .try_for_each(|(_, db)| db.export())?;
-
-        self.metadata.export()?;
-
-        Ok(())
+            .try_for_each(|(_, db)| db.export())
     }
 
     fn reset_metadata(&mut self) {
@@ -229,6 +229,33 @@ impl AnyDatabaseGroup for AddressToAddressIndex {
         }
     }
 
+    fn stage(&self, height: usize) -> color_eyre::Result<()> {
+        self.p2pk
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+        self.p2pkh
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+        self.p2sh
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+        self.p2wpkh
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+        self.p2wsh
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+        self.p2tr
+            .par_iter()
+            .try_for_each(|(_, db)| db.stage(height))?;
+
+        self.unknown.iter().try_for_each(|db| db.stage(height))?;
+        self.empty.iter().try_for_each(|db| db.stage(height))?;
+        self.multisig.iter().try_for_each(|db| db.stage(height))?;
+
+        self.metadata.stage(height)
+    }
+
     fn export(&mut self) -> color_eyre::Result<()> {
         thread::scope(|s| {
             s.spawn(|| {
@@ -267,8 +294,6 @@ impl AnyDatabaseGroup for AddressToAddressIndex {
             s.spawn(|| self.multisig.take().map(|db| db.export()));
         });
 
-        self.metadata.export()?;
-
         Ok(())
     }
 
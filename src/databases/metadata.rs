@@ -6,7 +6,7 @@ use std::{
 };
 
 use crate::{
-    io::Binary,
+    io::{staged_path, Binary},
     parse::{Counter, WNaiveDate},
 };
 
@@ -38,8 +38,9 @@ impl Metadata {
         }
     }
 
-    pub fn export(&self) -> color_eyre::Result<()> {
-        self.data.export(&self.path)
+    /// Becomes the current version once the checkpoint at `height` is committed
+    pub fn stage(&self, height: usize) -> color_eyre::Result<()> {
+        self.data.stage(&self.path, height)
     }
 
     pub fn reset(&mut self) {
@@ -70,14 +71,14 @@ impl MetadataData {
         Binary::import(&Self::full_path(path))
     }
 
-    pub fn export(&self, path: &str) -> color_eyre::Result<()> {
-        Binary::export(&Self::full_path(path), self)
+    pub fn stage(&self, path: &str, height: usize) -> color_eyre::Result<()> {
+        Binary::export(&staged_path(&Self::full_path(path), height), self)
     }
 
     pub fn reset(&mut self, path: &str) -> color_eyre::Result<(), io::Error> {
         self.clear();
 
-        fs::remove_file(Self::full_path(path))
+        Binary::remove(&Self::full_path(path))
     }
 
     fn clear(&mut self) {
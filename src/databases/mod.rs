@@ -35,6 +35,21 @@ impl Databases {
         }
     }
 
+    pub fn stage(&self, height: usize) -> color_eyre::Result<()> {
+        thread::scope(|s| {
+            let address_index_to_empty_address_data_handle =
+                s.spawn(|| self.address_index_to_empty_address_data.stage(height));
+            let address_to_address_index_handle =
+                s.spawn(|| self.address_to_address_index.stage(height));
+            let txid_to_tx_index_handle = s.spawn(|| self.txid_to_tx_index.stage(height));
+
+            address_index_to_empty_address_data_handle.join().unwrap()?;
+            address_to_address_index_handle.join().unwrap()?;
+            txid_to_tx_index_handle.join().unwrap()
+        })
+    }
+
+    /// Applies what was staged, to be called once the checkpoint is committed
     pub fn export(&mut self) -> color_eyre::Result<()> {
         thread::scope(|s| {
             s.spawn(|| {
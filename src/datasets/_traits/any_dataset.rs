@@ -1,8 +1,10 @@
+use std::ops::Range;
+
 use chrono::NaiveDate;
 use itertools::Itertools;
 use rayon::prelude::*;
 
-use crate::parse::{AnyBiMap, AnyDateMap, AnyHeightMap, AnyMap};
+use crate::parse::{AnyBiMap, AnyDateMap, AnyDollarBiMap, AnyHeightMap, AnyMap};
 
 use super::MinInitialState;
 
@@ -69,6 +71,11 @@ pub trait AnyDataset {
         vec![]
     }
 
+    /// Subset of the bi maps whose values are in dollars
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![]
+    }
+
     fn to_any_mut_height_map_vec(&mut self) -> Vec<&mut dyn AnyHeightMap> {
         vec![]
     }
@@ -132,6 +139,67 @@ pub trait AnyDataset {
             .try_for_each(|map| -> color_eyre::Result<()> { map.export() })
     }
 
+    fn reset(&mut self) -> color_eyre::Result<()> {
+        self.to_any_mut_height_map_vec()
+            .into_iter()
+            .try_for_each(|d| d.as_any_mut_map().reset())?;
+
+        self.to_any_mut_date_map_vec()
+            .into_iter()
+            .try_for_each(|d| d.as_any_mut_map().reset())?;
+
+        self.to_any_mut_bi_map_vec().into_iter().try_for_each(|d| {
+            d.as_any_mut_map()
+                .into_iter()
+                .try_for_each(|map| map.reset())
+        })
+    }
+
+    /// Removes everything from `height` and `date` onwards
+    fn rollback(&mut self, height: usize, date: NaiveDate) -> color_eyre::Result<()> {
+        self.to_any_mut_height_map_vec()
+            .into_iter()
+            .try_for_each(|map| map.truncate(height))?;
+
+        self.to_any_mut_date_map_vec()
+            .into_iter()
+            .try_for_each(|map| map.truncate(date))?;
+
+        self.to_any_mut_bi_map_vec()
+            .into_iter()
+            .try_for_each(|map| -> color_eyre::Result<()> {
+                map.get_mut_height().truncate(height)?;
+                map.get_mut_date().truncate(date)
+            })
+    }
+
+    /// `date` being the one of the block at `height`
+    fn set_first_provisional_height(&mut self, height: Option<usize>, date: Option<NaiveDate>) {
+        self.to_any_mut_height_map_vec()
+            .into_iter()
+            .for_each(|map| map.set_first_provisional_height(height));
+
+        self.to_any_mut_date_map_vec()
+            .into_iter()
+            .for_each(|map| map.set_first_provisional_date(date));
+
+        self.to_any_mut_bi_map_vec().into_iter().for_each(|map| {
+            map.get_mut_height().set_first_provisional_height(height);
+            map.get_mut_date().set_first_provisional_date(date);
+        });
+    }
+
+    /// For datasets added while parsing, whose maps need to start at the beginning of a chunk
+    fn fill_default_heights(&mut self, heights: Range<usize>) {
+        self.to_any_mut_height_map_vec()
+            .into_iter()
+            .for_each(|map| map.fill_default(heights.clone()));
+
+        self.to_any_mut_bi_map_vec()
+            .into_iter()
+            .for_each(|map| map.get_mut_height().fill_default(heights.clone()));
+    }
+
     fn post_export(&mut self) {
         self.to_any_mut_height_map_vec()
             .into_iter()
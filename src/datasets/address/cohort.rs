@@ -3,7 +3,7 @@ use itertools::Itertools;
 
 use crate::{
     datasets::{AnyDataset, AnyDatasetGroup, MinInitialState, ProcessedBlockData, SubDataset},
-    parse::{AddressSplit, AnyBiMap, AnyDateMap, AnyHeightMap},
+    parse::{AddressSplit, AnyBiMap, AnyDateMap, AnyDollarBiMap, AnyHeightMap},
     states::AddressCohortDurableStates,
 };
 
@@ -88,6 +88,12 @@ impl CohortDataset {
             .any(|sub| sub.price_paid.should_insert(height, date))
     }
 
+    fn needs_ratios_data(&self, date: NaiveDate, height: usize) -> bool {
+        self.sub_datasets_vec()
+            .iter()
+            .any(|sub| sub.ratios.should_insert(height, date))
+    }
+
     fn needs_realized_data(&self, date: NaiveDate, height: usize) -> bool {
         self.sub_datasets_vec()
             .iter()
@@ -233,6 +239,43 @@ impl CohortDataset {
         );
     }
 
+    fn insert_ratios_data(
+        &mut self,
+        processed_block_data: &ProcessedBlockData,
+        liquidity_split_state: &AddressCohortDurableStates,
+    ) {
+        let states = processed_block_data
+            .address_cohorts_one_shot_states
+            .as_ref()
+            .unwrap()
+            .get_state(&self.split)
+            .unwrap();
+
+        self.all.ratios.insert(
+            processed_block_data,
+            &liquidity_split_state.split.all.supply_state,
+            &states.all,
+        );
+
+        self.illiquid.ratios.insert(
+            processed_block_data,
+            &liquidity_split_state.split.illiquid.supply_state,
+            &states.illiquid,
+        );
+
+        self.liquid.ratios.insert(
+            processed_block_data,
+            &liquidity_split_state.split.liquid.supply_state,
+            &states.liquid,
+        );
+
+        self.highly_liquid.ratios.insert(
+            processed_block_data,
+            &liquidity_split_state.split.highly_liquid.supply_state,
+            &states.highly_liquid,
+        );
+    }
+
     fn insert_price_paid_data(&mut self, processed_block_data: &ProcessedBlockData) {
         let states = processed_block_data
             .address_cohorts_one_shot_states
@@ -286,6 +329,28 @@ impl CohortDataset {
         );
     }
 
+    fn insert_urpd_data(&mut self, processed_block_data: &ProcessedBlockData) {
+        let Some(states) = processed_block_data
+            .address_cohorts_one_shot_states
+            .as_ref()
+            .and_then(|states| states.get_state(&self.split))
+        else {
+            return;
+        };
+
+        let date = processed_block_data.date;
+
+        [
+            (&mut self.all, &states.all),
+            (&mut self.illiquid, &states.illiquid),
+            (&mut self.liquid, &states.liquid),
+            (&mut self.highly_liquid, &states.highly_liquid),
+        ]
+        .into_iter()
+        .filter_map(|(sub, states)| Some((sub, states.urpd.as_ref()?)))
+        .for_each(|(sub, urpd)| sub.urpd.insert(date, urpd.clone()));
+    }
+
     fn insert_input_data(&mut self, processed_block_data: &ProcessedBlockData) {
         let state = processed_block_data
             .address_cohorts_input_states
@@ -352,13 +417,17 @@ impl CohortDataset {
         .collect_vec()
     }
 
-    pub fn insert_data(&mut self, processed_block_data: &ProcessedBlockData) {
+    pub fn insert_data(
+        &mut self,
+        processed_block_data: &ProcessedBlockData,
+    ) -> color_eyre::Result<()> {
         let &ProcessedBlockData { height, date, .. } = processed_block_data;
 
         let needs_metadata = self.needs_metadata(date, height);
         let needs_unrealized_data = self.needs_unrealized_data(date, height);
         let needs_realized = self.needs_realized_data(date, height);
         let needs_price_paid = self.needs_price_paid_data(date, height);
+        let needs_ratios = self.needs_ratios_data(date, height);
         let needs_supply = needs_price_paid || self.needs_supply_data(date, height);
         let needs_utxo = self.needs_utxo_data(date, height);
         let needs_input = self.needs_input_data(date, height);
@@ -370,7 +439,7 @@ impl CohortDataset {
             .get_state(&self.split);
 
         if liquidity_split_processed_address_state.is_none() {
-            return; // TODO: Check if should panic instead
+            return Ok(()); // TODO: Check if should panic instead
         }
 
         let liquidity_split_processed_address_state =
@@ -407,6 +476,13 @@ impl CohortDataset {
             self.insert_price_paid_data(processed_block_data);
         }
 
+        if needs_ratios {
+            self.insert_ratios_data(
+                processed_block_data,
+                liquidity_split_processed_address_state,
+            );
+        }
+
         if needs_input {
             self.insert_input_data(processed_block_data);
         }
@@ -414,6 +490,10 @@ impl CohortDataset {
         if needs_output {
             self.insert_output_data(processed_block_data);
         }
+
+        self.insert_urpd_data(processed_block_data);
+
+        Ok(())
     }
 }
 
@@ -463,4 +543,11 @@ impl AnyDataset for CohortDataset {
     fn get_min_initial_state(&self) -> &MinInitialState {
         &self.min_initial_state
     }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        self.as_vec()
+            .into_iter()
+            .flat_map(|d| d.to_dollar_bi_map_vec())
+            .collect_vec()
+    }
 }
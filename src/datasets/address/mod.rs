@@ -170,26 +170,31 @@ impl AddressDatasets {
         })
     }
 
-    pub fn insert_data(&mut self, processed_block_data: &ProcessedBlockData) {
+    pub fn insert_data(
+        &mut self,
+        processed_block_data: &ProcessedBlockData,
+    ) -> color_eyre::Result<()> {
         self.metadata.insert_data(processed_block_data);
 
-        self.all.insert_data(processed_block_data);
-
-        self.plankton.insert_data(processed_block_data);
-        self.shrimp.insert_data(processed_block_data);
-        self.crab.insert_data(processed_block_data);
-        self.fish.insert_data(processed_block_data);
-        self.shark.insert_data(processed_block_data);
-        self.whale.insert_data(processed_block_data);
-        self.humpback.insert_data(processed_block_data);
-        self.megalodon.insert_data(processed_block_data);
-
-        self.p2pk.insert_data(processed_block_data);
-        self.p2pkh.insert_data(processed_block_data);
-        self.p2sh.insert_data(processed_block_data);
-        self.p2wpkh.insert_data(processed_block_data);
-        self.p2wsh.insert_data(processed_block_data);
-        self.p2tr.insert_data(processed_block_data);
+        self.all.insert_data(processed_block_data)?;
+
+        self.plankton.insert_data(processed_block_data)?;
+        self.shrimp.insert_data(processed_block_data)?;
+        self.crab.insert_data(processed_block_data)?;
+        self.fish.insert_data(processed_block_data)?;
+        self.shark.insert_data(processed_block_data)?;
+        self.whale.insert_data(processed_block_data)?;
+        self.humpback.insert_data(processed_block_data)?;
+        self.megalodon.insert_data(processed_block_data)?;
+
+        self.p2pk.insert_data(processed_block_data)?;
+        self.p2pkh.insert_data(processed_block_data)?;
+        self.p2sh.insert_data(processed_block_data)?;
+        self.p2wpkh.insert_data(processed_block_data)?;
+        self.p2wsh.insert_data(processed_block_data)?;
+        self.p2tr.insert_data(processed_block_data)?;
+
+        Ok(())
     }
 }
 
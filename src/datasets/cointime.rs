@@ -1,8 +1,8 @@
 use crate::{
     bitcoin::{
-        sats_to_btc, ONE_DAY_IN_BLOCK_TIME, THREE_MONTHS_IN_BLOCK_TIME, TWO_WEEKS_IN_BLOCK_TIME,
+        sats_to_btc_f64, ONE_DAY_IN_BLOCK_TIME, THREE_MONTHS_IN_BLOCK_TIME, TWO_WEEKS_IN_BLOCK_TIME,
     },
-    parse::{AnyBiMap, BiMap},
+    parse::{AnyBiMap, AnyDollarBiMap, BiMap},
     utils::{ONE_DAY_IN_DAYS, ONE_YEAR_IN_DAYS, THREE_MONTHS_IN_DAYS, TWO_WEEK_IN_DAYS},
 };
 
@@ -14,42 +14,42 @@ use super::{
 pub struct CointimeDataset {
     min_initial_state: MinInitialState,
 
-    pub active_cap: BiMap<f32>,
+    pub active_cap: BiMap<f64>,
     pub active_price: BiMap<f32>,
     pub active_supply: BiMap<f32>,
     pub active_supply_3m_net_change: BiMap<f32>,
     pub active_supply_net_change: BiMap<f32>,
     pub activity_to_vaultedness_ratio: BiMap<f32>,
-    pub coinblocks_created: BiMap<f32>,
-    pub coinblocks_destroyed: BiMap<f32>,
-    pub coinblocks_stored: BiMap<f32>,
+    pub coinblocks_created: BiMap<f64>,
+    pub coinblocks_destroyed: BiMap<f64>,
+    pub coinblocks_stored: BiMap<f64>,
     pub cointime_adjusted_velocity: BiMap<f32>,
     pub cointime_adjusted_yearly_inflation_rate: BiMap<f32>,
-    pub cointime_cap: BiMap<f32>,
+    pub cointime_cap: BiMap<f64>,
     pub cointime_price: BiMap<f32>,
-    pub cointime_value_created: BiMap<f32>,
-    pub cointime_value_destroyed: BiMap<f32>,
-    pub cointime_value_stored: BiMap<f32>,
+    pub cointime_value_created: BiMap<f64>,
+    pub cointime_value_destroyed: BiMap<f64>,
+    pub cointime_value_stored: BiMap<f64>,
     pub concurrent_liveliness: BiMap<f32>,
     pub concurrent_liveliness_2w_median: BiMap<f32>,
-    pub cumulative_coinblocks_created: BiMap<f32>,
-    pub cumulative_coinblocks_destroyed: BiMap<f32>,
-    pub cumulative_coinblocks_stored: BiMap<f32>,
-    pub investor_cap: BiMap<f32>,
+    pub cumulative_coinblocks_created: BiMap<f64>,
+    pub cumulative_coinblocks_destroyed: BiMap<f64>,
+    pub cumulative_coinblocks_stored: BiMap<f64>,
+    pub investor_cap: BiMap<f64>,
     pub investorness: BiMap<f32>,
     pub liveliness: BiMap<f32>,
     pub liveliness_net_change: BiMap<f32>,
     pub liveliness_net_change_2w_median: BiMap<f32>,
     pub producerness: BiMap<f32>,
-    pub thermo_cap: BiMap<f32>,
+    pub thermo_cap: BiMap<f64>,
     pub thermo_cap_to_investor_cap_ratio: BiMap<f32>,
-    pub total_cointime_value_created: BiMap<f32>,
-    pub total_cointime_value_destroyed: BiMap<f32>,
-    pub total_cointime_value_stored: BiMap<f32>,
+    pub total_cointime_value_created: BiMap<f64>,
+    pub total_cointime_value_destroyed: BiMap<f64>,
+    pub total_cointime_value_stored: BiMap<f64>,
     pub true_market_deviation: BiMap<f32>,
     pub true_market_mean: BiMap<f32>,
     pub true_market_net_unrealized_profit_and_loss: BiMap<f32>,
-    pub vaulted_cap: BiMap<f32>,
+    pub vaulted_cap: BiMap<f64>,
     pub vaulted_price: BiMap<f32>,
     pub vaulted_supply: BiMap<f32>,
     pub vaultedness: BiMap<f32>,
@@ -63,15 +63,15 @@ impl CointimeDataset {
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
 
-            coinblocks_destroyed: BiMap::new_bin(1, &f("coinblocks_destroyed")),
+            coinblocks_destroyed: BiMap::new_bin(2, &f("coinblocks_destroyed")),
             cumulative_coinblocks_destroyed: BiMap::new_bin(
-                1,
+                2,
                 &f("cumulative_coinblocks_destroyed"),
             ),
-            coinblocks_created: BiMap::new_bin(1, &f("coinblocks_created")),
-            cumulative_coinblocks_created: BiMap::new_bin(1, &f("cumulative_coinblocks_created")),
-            coinblocks_stored: BiMap::new_bin(1, &f("coinblocks_stored")),
-            cumulative_coinblocks_stored: BiMap::new_bin(1, &f("cumulative_coinblocks_stored")),
+            coinblocks_created: BiMap::new_bin(2, &f("coinblocks_created")),
+            cumulative_coinblocks_created: BiMap::new_bin(2, &f("cumulative_coinblocks_created")),
+            coinblocks_stored: BiMap::new_bin(2, &f("coinblocks_stored")),
+            cumulative_coinblocks_stored: BiMap::new_bin(2, &f("cumulative_coinblocks_stored")),
             liveliness: BiMap::_new_bin(1, &f("liveliness"), 2),
             vaultedness: BiMap::new_bin(1, &f("vaultedness")),
             activity_to_vaultedness_ratio: BiMap::new_bin(1, &f("activity_to_vaultedness_ratio")),
@@ -95,16 +95,16 @@ impl CointimeDataset {
                 &f("cointime_adjusted_yearly_inflation_rate"),
             ),
             cointime_adjusted_velocity: BiMap::new_bin(1, &f("cointime_adjusted_velocity")),
-            thermo_cap: BiMap::new_bin(1, &f("thermo_cap")),
-            investor_cap: BiMap::new_bin(1, &f("investor_cap")),
+            thermo_cap: BiMap::new_bin(2, &f("thermo_cap")),
+            investor_cap: BiMap::new_bin(2, &f("investor_cap")),
             thermo_cap_to_investor_cap_ratio: BiMap::new_bin(
                 1,
                 &f("thermo_cap_to_investor_cap_ratio"),
             ),
             active_price: BiMap::new_bin(1, &f("active_price")),
-            active_cap: BiMap::new_bin(1, &f("active_cap")),
+            active_cap: BiMap::new_bin(2, &f("active_cap")),
             vaulted_price: BiMap::new_bin(1, &f("vaulted_price")),
-            vaulted_cap: BiMap::new_bin(1, &f("vaulted_cap")),
+            vaulted_cap: BiMap::new_bin(2, &f("vaulted_cap")),
             true_market_mean: BiMap::new_bin(1, &f("true_market_mean")),
             true_market_deviation: BiMap::new_bin(1, &f("true_market_deviation")),
             true_market_net_unrealized_profit_and_loss: BiMap::new_bin(
@@ -113,14 +113,14 @@ impl CointimeDataset {
             ),
             investorness: BiMap::new_bin(1, &f("investorness")),
             producerness: BiMap::new_bin(1, &f("producerness")),
-            cointime_value_created: BiMap::new_bin(1, &f("cointime_value_created")),
-            cointime_value_destroyed: BiMap::new_bin(1, &f("cointime_value_destroyed")),
-            cointime_value_stored: BiMap::new_bin(1, &f("cointime_value_stored")),
-            total_cointime_value_created: BiMap::new_bin(1, &f("total_cointime_value_created")),
-            total_cointime_value_destroyed: BiMap::new_bin(1, &f("total_cointime_value_destroyed")),
-            total_cointime_value_stored: BiMap::new_bin(1, &f("total_cointime_value_stored")),
+            cointime_value_created: BiMap::new_bin(2, &f("cointime_value_created")),
+            cointime_value_destroyed: BiMap::new_bin(2, &f("cointime_value_destroyed")),
+            cointime_value_stored: BiMap::new_bin(2, &f("cointime_value_stored")),
+            total_cointime_value_created: BiMap::new_bin(2, &f("total_cointime_value_created")),
+            total_cointime_value_destroyed: BiMap::new_bin(2, &f("total_cointime_value_destroyed")),
+            total_cointime_value_stored: BiMap::new_bin(2, &f("total_cointime_value_stored")),
             cointime_price: BiMap::new_bin(1, &f("cointime_price")),
-            cointime_cap: BiMap::new_bin(1, &f("cointime_cap")),
+            cointime_cap: BiMap::new_bin(2, &f("cointime_cap")),
         };
 
         s.min_initial_state
@@ -172,7 +172,7 @@ impl CointimeDataset {
         let coinblocks_destroyed = self
             .coinblocks_destroyed
             .height
-            .insert(height, sats_to_btc(satblocks_destroyed));
+            .insert(height, sats_to_btc_f64(satblocks_destroyed));
 
         let cumulative_coinblocks_destroyed = self
             .cumulative_coinblocks_destroyed
@@ -182,7 +182,7 @@ impl CointimeDataset {
         let coinblocks_created = self
             .coinblocks_created
             .height
-            .insert(height, circulating_supply);
+            .insert(height, circulating_supply as f64);
 
         let cumulative_coinblocks_created = self
             .cumulative_coinblocks_created
@@ -201,7 +201,7 @@ impl CointimeDataset {
 
         let liveliness = self.liveliness.height.insert(
             height,
-            cumulative_coinblocks_destroyed / cumulative_coinblocks_created,
+            (cumulative_coinblocks_destroyed / cumulative_coinblocks_created) as f32,
         );
 
         let vaultedness = self.vaultedness.height.insert(height, 1.0 - liveliness);
@@ -214,7 +214,7 @@ impl CointimeDataset {
         let concurrent_liveliness = self
             .concurrent_liveliness
             .height
-            .insert(height, coinblocks_destroyed / coinblocks_created);
+            .insert(height, (coinblocks_destroyed / coinblocks_created) as f32);
 
         self.concurrent_liveliness_2w_median.height.insert_median(
             height,
@@ -292,7 +292,7 @@ impl CointimeDataset {
 
         self.thermo_cap_to_investor_cap_ratio
             .height
-            .insert(height, thermo_cap / investor_cap);
+            .insert(height, (thermo_cap / investor_cap) as f32);
 
         // TODO:
         // const activeSupplyChangeFromIssuance90dChange = createNetChangeLazyDataset(
@@ -307,7 +307,7 @@ impl CointimeDataset {
         let active_cap = self
             .active_cap
             .height
-            .insert(height, active_supply * block_price);
+            .insert(height, active_supply as f64 * block_price as f64);
 
         self.vaulted_price
             .height
@@ -315,45 +315,45 @@ impl CointimeDataset {
 
         self.vaulted_cap
             .height
-            .insert(height, vaulted_supply * block_price);
+            .insert(height, vaulted_supply as f64 * block_price as f64);
 
         let true_market_mean = self
             .true_market_mean
             .height
-            .insert(height, investor_cap / active_supply);
+            .insert(height, (investor_cap / active_supply as f64) as f32);
 
         let true_market_deviation = self
             .true_market_deviation
             .height
-            .insert(height, active_cap / investor_cap);
+            .insert(height, (active_cap / investor_cap) as f32);
 
         let true_market_net_unrealized_profit_and_loss = self
             .true_market_net_unrealized_profit_and_loss
             .height
-            .insert(height, (active_cap - investor_cap) / active_cap);
+            .insert(height, ((active_cap - investor_cap) / active_cap) as f32);
 
         self.investorness
             .height
-            .insert(height, investor_cap / realized_cap);
+            .insert(height, (investor_cap / realized_cap) as f32);
 
         self.producerness
             .height
-            .insert(height, thermo_cap / realized_cap);
+            .insert(height, (thermo_cap / realized_cap) as f32);
 
         let cointime_value_destroyed = self
             .cointime_value_destroyed
             .height
-            .insert(height, block_price * coinblocks_destroyed);
+            .insert(height, block_price as f64 * coinblocks_destroyed);
 
         let cointime_value_created = self
             .cointime_value_created
             .height
-            .insert(height, block_price * coinblocks_created);
+            .insert(height, block_price as f64 * coinblocks_created);
 
         let cointime_value_stored = self
             .cointime_value_stored
             .height
-            .insert(height, block_price * coinblocks_stored);
+            .insert(height, block_price as f64 * coinblocks_stored);
 
         let total_cointime_value_created = self
             .total_cointime_value_created
@@ -372,13 +372,13 @@ impl CointimeDataset {
 
         let cointime_price = self.cointime_price.height.insert(
             height,
-            total_cointime_value_destroyed / cumulative_coinblocks_stored,
+            (total_cointime_value_destroyed / cumulative_coinblocks_stored) as f32,
         );
 
         let cointime_cap = self
             .cointime_cap
             .height
-            .insert(height, cointime_price * circulating_supply);
+            .insert(height, cointime_price as f64 * circulating_supply as f64);
 
         if is_date_last_block {
             let realized_cap = realized_cap_map.date.get(date).unwrap();
@@ -478,7 +478,7 @@ impl CointimeDataset {
 
             self.thermo_cap_to_investor_cap_ratio
                 .date
-                .insert(date, thermo_cap / investor_cap);
+                .insert(date, (thermo_cap / investor_cap) as f32);
 
             self.active_price
                 .date
@@ -486,7 +486,7 @@ impl CointimeDataset {
 
             self.active_cap
                 .date
-                .insert(date, active_supply * date_price);
+                .insert(date, active_supply as f64 * date_price as f64);
 
             self.vaulted_price
                 .date
@@ -494,7 +494,7 @@ impl CointimeDataset {
 
             self.vaulted_cap
                 .date
-                .insert(date, vaulted_supply * date_price);
+                .insert(date, vaulted_supply as f64 * date_price as f64);
 
             self.true_market_mean.date.insert(date, true_market_mean);
 
@@ -508,11 +508,11 @@ impl CointimeDataset {
 
             self.investorness
                 .date
-                .insert(date, investor_cap / realized_cap);
+                .insert(date, (investor_cap / realized_cap) as f32);
 
             self.producerness
                 .date
-                .insert(date, thermo_cap / realized_cap);
+                .insert(date, (thermo_cap / realized_cap) as f32);
 
             self.cointime_value_destroyed
                 .date
@@ -639,4 +639,24 @@ impl AnyDataset for CointimeDataset {
     fn get_min_initial_state(&self) -> &MinInitialState {
         &self.min_initial_state
     }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![
+            &self.thermo_cap,
+            &self.investor_cap,
+            &self.active_price,
+            &self.active_cap,
+            &self.vaulted_price,
+            &self.vaulted_cap,
+            &self.true_market_mean,
+            &self.cointime_value_created,
+            &self.cointime_value_destroyed,
+            &self.cointime_value_stored,
+            &self.total_cointime_value_created,
+            &self.total_cointime_value_destroyed,
+            &self.total_cointime_value_stored,
+            &self.cointime_price,
+            &self.cointime_cap,
+        ]
+    }
 }
@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+
+use crate::{
+    datasets::AnyDataset,
+    parse::{AnyBiMap, AnyDollarBiMap, AnyHeightMap, BiMap, HEIGHT_MAP_CHUNK_SIZE},
+    price::CurrencyRates,
+};
+
+use super::{MinInitialState, ProcessedBlockData};
+
+///
+/// Every map in dollars of the other datasets, converted to another currency with its daily rate.
+///
+/// Each one is saved next to its source, at `{source_path}/{currency}`.
+/// Maps in dollars of the cohorts added while parsing get their converted ones at their first block.
+///
+pub struct CurrencyDataset {
+    min_initial_state: MinInitialState,
+
+    currency: String,
+    rates: CurrencyRates,
+    /// Given to the maps that are added while parsing
+    pub first_provisional_height: Option<usize>,
+    pub first_provisional_date: Option<NaiveDate>,
+
+    /// By the path of the map in dollars
+    pub maps: BTreeMap<String, BiMap<f64>>,
+}
+
+impl CurrencyDataset {
+    pub fn import<'a>(
+        currency: &str,
+        dollar_paths: impl Iterator<Item = &'a str>,
+    ) -> color_eyre::Result<Self> {
+        let mut s = Self {
+            min_initial_state: MinInitialState::default(),
+
+            currency: currency.to_owned(),
+            rates: CurrencyRates::import(currency)?,
+            first_provisional_height: None,
+            first_provisional_date: None,
+
+            maps: dollar_paths
+                .map(|path| {
+                    (
+                        path.to_owned(),
+                        BiMap::new_bin(1, &format!("{path}/{currency}")),
+                    )
+                })
+                .collect(),
+        };
+
+        s.min_initial_state
+            .consume(MinInitialState::compute_from_dataset(&s));
+
+        Ok(s)
+    }
+
+    pub fn insert_data(
+        &mut self,
+        &ProcessedBlockData {
+            height,
+            date,
+            is_date_last_block,
+            ..
+        }: &ProcessedBlockData,
+        dollar_maps: &[&(dyn AnyDollarBiMap + Send + Sync)],
+    ) -> color_eyre::Result<()> {
+        // Before the first rate of the file, values are left unset
+        let Some(rate) = self.rates.get(date)? else {
+            return Ok(());
+        };
+
+        for dollar_map in dollar_maps {
+            let map = self
+                .maps
+                .get_mut(dollar_map.path())
+                .expect("Missing maps to be added before inserting");
+
+            // Not computed, like the address cohorts when they're disabled
+            if let Some(dollars) = dollar_map.get_height_in_dollars(height) {
+                map.height.insert(height, dollars / rate);
+            }
+
+            if is_date_last_block {
+                if let Some(dollars) = dollar_map.get_date_in_dollars(date) {
+                    map.date.insert(date, dollars / rate);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Adds the maps converting the ones in dollars of the cohorts added at `height`, returns whether any was.
+    ///
+    /// They're default filled like the cohorts they convert, from the chunk of the previous height.
+    ///
+    pub fn add_missing_maps(
+        &mut self,
+        height: usize,
+        dollar_maps: &[&(dyn AnyDollarBiMap + Send + Sync)],
+    ) -> bool {
+        let start = height.checked_sub(1).map_or(0, |previous| {
+            previous / HEIGHT_MAP_CHUNK_SIZE * HEIGHT_MAP_CHUNK_SIZE
+        });
+
+        let mut added = false;
+
+        dollar_maps
+            .iter()
+            .map(|dollar_map| dollar_map.path())
+            .for_each(|path| {
+                if self.maps.contains_key(path) {
+                    return;
+                }
+
+                let mut map = BiMap::new_bin(1, &format!("{path}/{}", self.currency));
+
+                map.height.fill_default(start..height);
+
+                map.height
+                    .set_first_provisional_height(self.first_provisional_height);
+                map.date
+                    .set_first_provisional_date(self.first_provisional_date);
+
+                self.maps.insert(path.to_owned(), map);
+
+                added = true;
+            });
+
+        added
+    }
+
+    /// Drops the maps whose source is gone, like the ones of the cohorts removed by a rollback
+    pub fn retain_maps<'a>(&mut self, dollar_paths: impl Iterator<Item = &'a str>) {
+        let dollar_paths = dollar_paths.collect::<BTreeSet<_>>();
+
+        self.maps
+            .retain(|path, _| dollar_paths.contains(path.as_str()));
+    }
+}
+
+impl AnyDataset for CurrencyDataset {
+    fn get_min_initial_state(&self) -> &MinInitialState {
+        &self.min_initial_state
+    }
+
+    fn to_any_bi_map_vec(&self) -> Vec<&(dyn AnyBiMap + Send + Sync)> {
+        self.maps
+            .values()
+            .map(|map| map as &(dyn AnyBiMap + Send + Sync))
+            .collect()
+    }
+
+    fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
+        self.maps
+            .values_mut()
+            .map(|map| map as &mut dyn AnyBiMap)
+            .collect()
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     bitcoin::{sats_to_btc, ONE_YEAR_IN_BLOCK_TIME},
     datasets::AnyDataset,
-    parse::{AnyBiMap, AnyDateMap, BiMap, DateMap},
+    parse::{AnyBiMap, AnyDateMap, AnyDollarBiMap, BiMap, DateMap},
     utils::{ONE_MONTH_IN_DAYS, ONE_WEEK_IN_DAYS, ONE_YEAR_IN_DAYS},
 };
 
@@ -13,8 +13,8 @@ pub struct MiningDataset {
     pub coinbase: BiMap<f32>,
     pub fees: BiMap<f32>,
     pub subsidy: BiMap<f32>,
-    pub subsidy_in_dollars: BiMap<f32>,
-    pub cumulative_subsidy_in_dollars: BiMap<f32>,
+    pub subsidy_in_dollars: BiMap<f64>,
+    pub cumulative_subsidy_in_dollars: BiMap<f64>,
     pub annualized_issuance: BiMap<f32>,
     pub yearly_inflation_rate: BiMap<f32>,
 
@@ -22,7 +22,7 @@ pub struct MiningDataset {
     pub blocks_mined_1w_sma: DateMap<f32>,
     pub blocks_mined_1m_sma: DateMap<f32>,
     pub last_subsidy: DateMap<f32>,
-    pub last_subsidy_in_dollars: DateMap<f32>,
+    pub last_subsidy_in_dollars: DateMap<f64>,
 }
 
 impl MiningDataset {
@@ -37,14 +37,14 @@ impl MiningDataset {
             fees: BiMap::new_bin(1, &f("fees")),
 
             subsidy: BiMap::_new_bin(1, &f("subsidy"), 5),
-            subsidy_in_dollars: BiMap::new_bin(1, &f("subsidy_in_dollars")),
-            cumulative_subsidy_in_dollars: BiMap::new_bin(1, &f("cumulative_subsidy_in_dollars")),
+            subsidy_in_dollars: BiMap::new_bin(2, &f("subsidy_in_dollars")),
+            cumulative_subsidy_in_dollars: BiMap::new_bin(2, &f("cumulative_subsidy_in_dollars")),
 
             annualized_issuance: BiMap::new_bin(1, &f("annualized_issuance")),
             yearly_inflation_rate: BiMap::new_bin(1, &f("yearly_inflation_rate")),
 
             last_subsidy: DateMap::new_bin(1, &f("last_subsidy")),
-            last_subsidy_in_dollars: DateMap::new_bin(1, &f("last_subsidy_in_dollars")),
+            last_subsidy_in_dollars: DateMap::new_bin(2, &f("last_subsidy_in_dollars")),
 
             blocks_mined_1w_sma: DateMap::new_bin(1, &f("blocks_mined_7d_sma")),
             blocks_mined_1m_sma: DateMap::new_bin(1, &f("blocks_mined_1m_sma")),
@@ -87,7 +87,7 @@ impl MiningDataset {
 
         self.subsidy.height.insert(height, subsidy);
 
-        let subsidy_in_dollars = subsidy * block_price;
+        let subsidy_in_dollars = subsidy as f64 * block_price as f64;
 
         self.subsidy_in_dollars
             .height
@@ -123,7 +123,7 @@ impl MiningDataset {
             let subsidy_in_dollars = self
                 .subsidy_in_dollars
                 .date
-                .insert(date, subsidy * date_price);
+                .insert(date, subsidy as f64 * date_price as f64);
 
             self.cumulative_subsidy_in_dollars
                 .date
@@ -210,4 +210,11 @@ impl AnyDataset for MiningDataset {
             &mut self.yearly_inflation_rate,
         ]
     }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![
+            &self.subsidy_in_dollars,
+            &self.cumulative_subsidy_in_dollars,
+        ]
+    }
 }
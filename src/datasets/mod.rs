@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, ops::RangeInclusive, thread};
+use std::{collections::BTreeMap, mem, ops::RangeInclusive, thread};
 
 use chrono::NaiveDate;
 use itertools::Itertools;
@@ -9,6 +9,7 @@ mod address;
 mod block_metadata;
 mod coindays;
 mod cointime;
+mod currency;
 mod date_metadata;
 mod mining;
 mod price;
@@ -21,6 +22,7 @@ pub use address::*;
 pub use block_metadata::*;
 pub use coindays::*;
 pub use cointime::*;
+pub use currency::*;
 pub use date_metadata::*;
 pub use mining::*;
 pub use price::*;
@@ -29,6 +31,7 @@ pub use transaction::*;
 pub use utxo::*;
 
 use crate::{
+    config::Config,
     databases::Databases,
     io::Json,
     parse::{AddressData, AddressRealizedData},
@@ -80,11 +83,14 @@ pub struct AllDatasets {
     pub date_metadata: DateMetadataDataset,
     pub mining: MiningDataset,
     pub transaction: TransactionDataset,
+
+    /// One per currency of the config
+    pub currencies: Vec<CurrencyDataset>,
 }
 
 impl AllDatasets {
     pub fn import() -> color_eyre::Result<Self> {
-        let path = "./datasets";
+        let path = &Config::get().datasets_path;
 
         thread::scope(|scope| {
             let date_metadata_handle = scope.spawn(|| DateMetadataDataset::import(path));
@@ -129,8 +135,25 @@ impl AllDatasets {
                 mining,
                 transaction,
                 utxo,
+                currencies: vec![],
             };
 
+            let dollar_maps = s
+                .to_any_dataset_vec()
+                .into_iter()
+                .flat_map(|dataset| dataset.to_dollar_bi_map_vec())
+                .collect_vec();
+
+            let currencies = Config::get()
+                .currencies
+                .iter()
+                .map(|currency| {
+                    CurrencyDataset::import(currency, dollar_maps.iter().map(|map| map.path()))
+                })
+                .collect::<color_eyre::Result<Vec<_>>>()?;
+
+            s.currencies = currencies;
+
             s.min_initial_state
                 .consume(MinInitialState::compute_from_datasets(&s));
 
@@ -140,12 +163,20 @@ impl AllDatasets {
         })
     }
 
-    pub fn insert_data(&mut self, processed_block_data: ProcessedBlockData) {
+    pub fn insert_data(
+        &mut self,
+        processed_block_data: ProcessedBlockData,
+    ) -> color_eyre::Result<()> {
         let ProcessedBlockData { height, date, .. } = processed_block_data;
 
-        self.address.insert_data(&processed_block_data);
+        // Mining, transaction and cointime need the supply and realized cap computed by the address cohorts
+        let compute_addresses = Config::get().compute_addresses;
 
-        self.utxo.insert_data(&processed_block_data);
+        if compute_addresses {
+            self.address.insert_data(&processed_block_data)?;
+        }
+
+        self.utxo.insert_data(&processed_block_data)?;
 
         if self.block_metadata.should_insert(height, date) {
             self.block_metadata.insert_data(&processed_block_data);
@@ -159,17 +190,17 @@ impl AllDatasets {
             self.coindays.insert_data(&processed_block_data);
         }
 
-        if self.mining.should_insert(height, date) {
+        if compute_addresses && self.mining.should_insert(height, date) {
             self.mining
                 .insert_data(&processed_block_data, &self.address);
         }
 
-        if self.transaction.should_insert(height, date) {
+        if compute_addresses && self.transaction.should_insert(height, date) {
             self.transaction
                 .insert_data(&processed_block_data, &self.address);
         }
 
-        if self.cointime.should_insert(height, date) {
+        if compute_addresses && self.cointime.should_insert(height, date) {
             self.cointime.insert_data(
                 &processed_block_data,
                 &self.address,
@@ -177,6 +208,41 @@ impl AllDatasets {
                 &self.transaction,
             );
         }
+
+        if self.currencies.is_empty() {
+            return Ok(());
+        }
+
+        // Last since they convert the values inserted above
+        let mut currencies = mem::take(&mut self.currencies);
+
+        let dollar_maps = self
+            .to_any_dataset_vec()
+            .into_iter()
+            .flat_map(|dataset| dataset.to_dollar_bi_map_vec())
+            .collect_vec();
+
+        // Before the filter, so that the maps of new cohorts are added even when a currency doesn't need the block
+        let mut added = false;
+
+        currencies.iter_mut().for_each(|currency| {
+            added |= currency.add_missing_maps(height, &dollar_maps);
+        });
+
+        let result = currencies
+            .iter_mut()
+            .filter(|currency| currency.should_insert(height, date))
+            .try_for_each(|currency| currency.insert_data(&processed_block_data, &dollar_maps));
+
+        self.currencies = currencies;
+
+        result?;
+
+        if added {
+            self.export_path_to_type()?;
+        }
+
+        Ok(())
     }
 
     pub fn export_path_to_type(&self) -> color_eyre::Result<()> {
@@ -191,10 +257,108 @@ impl AllDatasets {
             })
             .collect();
 
-        Json::export("./datasets/paths.json", &path_to_type)
+        Json::export(
+            &format!("{}/paths.json", Config::get().datasets_path),
+            &path_to_type,
+        )
+    }
+
+    /// Prices are kept since they're slow (or even impossible without a .har file) to fetch again
+    pub fn reset(&mut self) -> color_eyre::Result<()> {
+        println!("Reseting all datasets...");
+
+        let mut datasets = vec![
+            self.address.to_mut_any_dataset_vec(),
+            self.utxo.to_mut_any_dataset_vec(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect_vec();
+
+        datasets.append(&mut vec![
+            &mut self.mining,
+            &mut self.transaction,
+            &mut self.block_metadata,
+            &mut self.date_metadata,
+            &mut self.cointime,
+            &mut self.coindays,
+        ]);
+
+        datasets.extend(
+            self.currencies
+                .iter_mut()
+                .map(|currency| currency as &mut dyn AnyDataset),
+        );
+
+        datasets.into_iter().try_for_each(|dataset| dataset.reset())
+    }
+
+    /// Daily prices don't depend on the chain, only the ones by height (and thus by timestamp) are rolled back
+    pub fn rollback(&mut self, height: usize, date: NaiveDate) -> color_eyre::Result<()> {
+        println!("Rolling back all datasets to {height} ({date})...");
+
+        let mut datasets = vec![
+            self.address.to_mut_any_dataset_vec(),
+            self.utxo.to_mut_any_dataset_vec(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect_vec();
+
+        datasets.append(&mut vec![
+            &mut self.price.height,
+            &mut self.mining,
+            &mut self.transaction,
+            &mut self.block_metadata,
+            &mut self.date_metadata,
+            &mut self.cointime,
+            &mut self.coindays,
+        ]);
+
+        datasets.extend(
+            self.currencies
+                .iter_mut()
+                .map(|currency| currency as &mut dyn AnyDataset),
+        );
+
+        datasets
+            .into_iter()
+            .try_for_each(|dataset| dataset.rollback(height, date))?;
+
+        self.utxo.remove_empty_cohorts()?;
+
+        let dollar_paths = self
+            .to_any_dataset_vec()
+            .into_iter()
+            .flat_map(|dataset| dataset.to_dollar_bi_map_vec())
+            .map(|map| map.path().to_owned())
+            .collect_vec();
+
+        self.currencies
+            .iter_mut()
+            .for_each(|currency| currency.retain_maps(dollar_paths.iter().map(String::as_str)));
+
+        Ok(())
+    }
+
+    /// Values from `height` (and `date`) onwards are exported in the provisional layer of each map
+    pub fn set_first_provisional_height(&mut self, height: Option<usize>, date: Option<NaiveDate>) {
+        self.utxo.first_provisional_height = height;
+        self.utxo.first_provisional_date = date;
+
+        self.currencies.iter_mut().for_each(|currency| {
+            currency.first_provisional_height = height;
+            currency.first_provisional_date = date;
+        });
+
+        self.to_mut_any_dataset_vec()
+            .into_iter()
+            .for_each(|dataset| dataset.set_first_provisional_height(height, date));
     }
 
     pub fn export(&mut self) -> color_eyre::Result<()> {
+        self.price.height.export_candles()?;
+
         self.to_mut_any_dataset_vec()
             .into_iter()
             .for_each(|dataset| dataset.pre_export());
@@ -229,6 +393,10 @@ impl AnyDatasets for AllDatasets {
                 &self.cointime,
                 &self.coindays,
             ],
+            self.currencies
+                .iter()
+                .map(|currency| currency as &(dyn AnyDataset + Send + Sync))
+                .collect_vec(),
         ]
         .into_iter()
         .flatten()
@@ -248,6 +416,10 @@ impl AnyDatasets for AllDatasets {
                 &mut self.cointime,
                 &mut self.coindays,
             ],
+            self.currencies
+                .iter_mut()
+                .map(|currency| currency as &mut dyn AnyDataset)
+                .collect_vec(),
         ]
         .into_iter()
         .flatten()
@@ -1,32 +1,39 @@
-use std::collections::HashMap;
-
 use chrono::NaiveDate;
-use color_eyre::eyre::Error;
 
 use crate::{
     datasets::{AnyDataset, MinInitialState},
     parse::{AnyDateMap, DateMap},
-    price::Kraken,
+    price::{MissingPrice, PriceSources},
 };
 
 pub struct DateDataset {
     min_initial_state: MinInitialState,
 
-    kraken_daily: Option<HashMap<String, f32>>,
+    sources: PriceSources,
 
+    pub opens: DateMap<f32>,
+    pub highs: DateMap<f32>,
+    pub lows: DateMap<f32>,
     pub closes: DateMap<f32>,
+    /// 0 when the source doesn't have it
+    pub volumes: DateMap<f32>,
 }
 
 impl DateDataset {
     pub fn import(parent_path: &str) -> color_eyre::Result<Self> {
-        let name = "close";
+        let f =
+            |name: &str| DateMap::_new_json(1, &format!("{parent_path}/{name}"), usize::MAX, true);
 
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
 
-            kraken_daily: None,
+            sources: PriceSources::new(),
 
-            closes: DateMap::_new_json(1, &format!("{parent_path}/{name}"), usize::MAX, true),
+            opens: f("open"),
+            highs: f("high"),
+            lows: f("low"),
+            closes: f("close"),
+            volumes: f("volume"),
         };
 
         s.min_initial_state
@@ -35,29 +42,41 @@ impl DateDataset {
         Ok(s)
     }
 
+    /// Dates whose close was stored before the other values of the candle get the candle of the sources
+    /// if they still have it, and stay without them otherwise until one does
     pub fn get(&mut self, date: NaiveDate) -> color_eyre::Result<f32> {
-        if self.closes.is_date_safe(date) {
-            Ok(self.closes.get(date).unwrap().to_owned())
+        let close = if self.closes.is_date_safe(date) {
+            self.closes.get(date)
         } else {
-            let price = self.get_from_daily_kraken(&date.to_string())?;
-
-            self.closes.insert(date, price);
+            None
+        };
 
-            Ok(price)
+        if let Some(close) = close {
+            if self.is_candle_complete(date) {
+                return Ok(close);
+            }
         }
-    }
 
-    fn get_from_daily_kraken(&mut self, date: &str) -> color_eyre::Result<f32> {
-        if self.kraken_daily.is_none() {
-            self.kraken_daily.replace(Kraken::fetch_daily_prices()?);
-        }
+        let candle = match (close, self.sources.get_daily(date)) {
+            (Some(close), Some(candle)) => candle.with_close(close),
+            (Some(close), None) => return Ok(close),
+            (None, Some(candle)) => candle,
+            (None, None) => return Err(MissingPrice::Date(date).into()),
+        };
+
+        self.opens.insert(date, candle.open);
+        self.highs.insert(date, candle.high);
+        self.lows.insert(date, candle.low);
+        self.closes.insert(date, candle.close);
+        self.volumes.insert(date, candle.volume.unwrap_or_default());
+
+        Ok(candle.close)
+    }
 
-        self.kraken_daily
-            .as_ref()
-            .unwrap()
-            .get(date)
-            .cloned()
-            .ok_or(Error::msg("Couldn't find date in daily kraken"))
+    fn is_candle_complete(&self, date: NaiveDate) -> bool {
+        [&self.opens, &self.highs, &self.lows, &self.volumes]
+            .iter()
+            .all(|map| map.get(date).is_some())
     }
 }
 
@@ -67,6 +86,12 @@ impl AnyDataset for DateDataset {
     }
 
     fn to_any_date_map_vec(&self) -> Vec<&(dyn AnyDateMap + Send + Sync)> {
-        vec![&self.closes]
+        vec![
+            &self.opens,
+            &self.highs,
+            &self.lows,
+            &self.closes,
+            &self.volumes,
+        ]
     }
 }
@@ -1,36 +1,48 @@
-use std::collections::BTreeMap;
-
 use chrono::{NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc};
-use color_eyre::eyre::Error;
 
 use crate::{
+    config::Config,
     datasets::{AnyDataset, MinInitialState},
     parse::{AnyHeightMap, HeightMap},
-    price::{Binance, Kraken},
+    price::{
+        check_close, Candle, CandleStore, MissingPrice, PriceFile, PriceSources, Quarantine,
+        QuarantinedCandle,
+    },
 };
 
+/// Candle of the minute of each block
 pub struct HeightDataset {
     min_initial_state: MinInitialState,
 
-    kraken_1mn: Option<BTreeMap<u32, f32>>,
-    binance_1mn: Option<BTreeMap<u32, f32>>,
-    binance_har: Option<BTreeMap<u32, f32>>,
+    sources: PriceSources,
+    quarantine: Quarantine,
 
+    /// 0 when unknown, like the highs and lows, since heights can't be skipped
+    pub opens: HeightMap<f32>,
+    pub highs: HeightMap<f32>,
+    pub lows: HeightMap<f32>,
     pub closes: HeightMap<f32>,
+    /// 0 when the source doesn't have it
+    pub volumes: HeightMap<f32>,
 }
 
 impl HeightDataset {
     pub fn import(parent_path: &str) -> color_eyre::Result<Self> {
-        let name = "close";
+        let f = |name: &str| {
+            HeightMap::_new_json(1, &format!("{parent_path}/{name}"), usize::MAX, false)
+        };
 
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
 
-            binance_1mn: None,
-            binance_har: None,
-            kraken_1mn: None,
+            sources: PriceSources::new(),
+            quarantine: Quarantine::import(),
 
-            closes: HeightMap::_new_json(1, &format!("{parent_path}/{name}"), usize::MAX, false),
+            opens: f("open"),
+            highs: f("high"),
+            lows: f("low"),
+            closes: f("close"),
+            volumes: f("volume"),
         };
 
         s.min_initial_state
@@ -39,70 +51,118 @@ impl HeightDataset {
         Ok(s)
     }
 
+    /// Heights whose close was stored before the other values of the candle get the candle of the sources
+    /// if they still have it, and stay incomplete otherwise until one does
     pub fn get(&mut self, height: usize, timestamp: u32) -> color_eyre::Result<f32> {
-        if let Some(price) = self.closes.get(&height) {
-            return Ok(price);
+        let close = self.closes.get(&height);
+
+        if let Some(close) = close {
+            if self.is_candle_complete(height) {
+                return Ok(close);
+            }
         }
 
         let date_time = Utc.timestamp_opt(i64::from(timestamp), 0).unwrap();
-        let timestamp = NaiveDateTime::new(
+        let minute = NaiveDateTime::new(
             date_time.date_naive(),
             NaiveTime::from_hms_opt(date_time.hour(), date_time.minute(), 0).unwrap(),
         )
         .and_utc()
         .timestamp() as u32;
 
-        let price = self.get_from_1mn_kraken(timestamp).unwrap_or_else(|_| {
-                self.get_from_1mn_binance(timestamp)
-                    .unwrap_or_else(|_| self.get_from_har_binance(timestamp).unwrap_or_else(|_| {
-                        panic!(
-                            "Can't find price for {height} - {date_time} - {timestamp}, please update binance.har file"
-                        )
-                    }))
-            });
+        let candle = match (close, self.sources.get_1mn(minute)?) {
+            (Some(close), Some((_, candle))) => candle.with_close(close),
+            (Some(close), None) => Candle::only_close(close),
+            (None, found) => self.trusted_candle(height, timestamp, minute, found)?,
+        };
 
-        self.closes.insert(height, price);
+        self.opens.insert(height, candle.open);
+        self.highs.insert(height, candle.high);
+        self.lows.insert(height, candle.low);
+        self.closes.insert(height, candle.close);
+        self.volumes
+            .insert(height, candle.volume.unwrap_or_default());
 
-        Ok(price)
+        Ok(candle.close)
     }
 
-    fn get_from_1mn_kraken(&mut self, timestamp: u32) -> color_eyre::Result<f32> {
-        if self.kraken_1mn.is_none() {
-            self.kraken_1mn.replace(Kraken::fetch_1mn_prices()?);
+    /// Candles of the store and of the prices file are trusted, the others need to pass the checks or be accepted
+    fn trusted_candle(
+        &mut self,
+        height: usize,
+        timestamp: u32,
+        minute: u32,
+        found: Option<(&'static str, Candle)>,
+    ) -> color_eyre::Result<Candle> {
+        if let Some(candle) = self.quarantine.take_accepted(height)? {
+            self.sources.keep_1mn(minute, candle);
+
+            return Ok(candle);
         }
 
-        self.kraken_1mn
-            .as_ref()
-            .unwrap()
-            .get(&timestamp)
-            .cloned()
-            .ok_or(Error::msg("Couldn't find timestamp in 1mn kraken"))
-    }
+        let Some((source, candle)) = found else {
+            return Err(MissingPrice::Height { height, timestamp }.into());
+        };
 
-    fn get_from_1mn_binance(&mut self, timestamp: u32) -> color_eyre::Result<f32> {
-        if self.binance_1mn.is_none() {
-            self.binance_1mn.replace(Binance::fetch_1mn_prices()?);
+        if source == CandleStore::NAME || source == PriceFile::NAME {
+            return Ok(candle);
         }
 
-        self.binance_1mn
-            .as_ref()
-            .unwrap()
-            .get(&timestamp)
-            .cloned()
-            .ok_or(Error::msg("Couldn't find timestamp in 1mn binance"))
-    }
+        let other_closes = self
+            .sources
+            .get_1mn_closes(minute)
+            .into_iter()
+            .filter(|(name, _)| *name != source)
+            .collect::<Vec<_>>();
+
+        let previous_close = if height > 0 {
+            self.closes.read(height - 1)?
+        } else {
+            None
+        };
 
-    fn get_from_har_binance(&mut self, timestamp: u32) -> color_eyre::Result<f32> {
-        if self.binance_har.is_none() {
-            self.binance_har.replace(Binance::read_har_file()?);
+        let config = Config::get();
+
+        let reasons = check_close(
+            candle.close,
+            &other_closes,
+            previous_close,
+            config.price_max_deviation,
+            config.price_max_gap,
+        );
+
+        if !reasons.is_empty() {
+            self.quarantine.insert(
+                height,
+                QuarantinedCandle {
+                    timestamp: minute,
+                    source: source.to_owned(),
+                    candle,
+                    reasons: reasons.clone(),
+                    accepted: false,
+                },
+            )?;
+
+            return Err(MissingPrice::Quarantined { height, reasons }.into());
         }
 
-        self.binance_har
-            .as_ref()
-            .unwrap()
-            .get(&timestamp)
-            .cloned()
-            .ok_or(Error::msg("Couldn't find timestamp in har binance"))
+        self.quarantine.remove(height)?;
+
+        self.sources.keep_1mn(minute, candle);
+
+        Ok(candle)
+    }
+
+    /// Candles of the sources kept for the store aren't maps and are written apart
+    pub fn export_candles(&mut self) -> color_eyre::Result<()> {
+        self.sources.export()
+    }
+
+    fn is_candle_complete(&self, height: usize) -> bool {
+        [&self.opens, &self.highs, &self.lows]
+            .iter()
+            .all(|map| map.get(&height).is_some_and(|value| value > 0.0))
+            && self.volumes.get(&height).is_some()
     }
 }
 
@@ -112,6 +172,12 @@ impl AnyDataset for HeightDataset {
     }
 
     fn to_any_height_map_vec(&self) -> Vec<&(dyn AnyHeightMap + Send + Sync)> {
-        vec![&self.closes]
+        vec![
+            &self.opens,
+            &self.highs,
+            &self.lows,
+            &self.closes,
+            &self.volumes,
+        ]
     }
 }
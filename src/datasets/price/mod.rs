@@ -5,6 +5,8 @@ use chrono::NaiveDate;
 use date::*;
 use height::*;
 
+use crate::config::Config;
+
 use super::{AnyDataset, AnyDatasets, MinInitialState};
 
 pub struct PriceDatasets {
@@ -16,7 +18,7 @@ pub struct PriceDatasets {
 
 impl PriceDatasets {
     pub fn import() -> color_eyre::Result<Self> {
-        let path = "./price";
+        let path = &Config::get().price_path;
 
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
@@ -1,17 +1,23 @@
 mod input;
 mod output;
 mod price_paid;
+mod ratios;
 mod realized;
+mod sopr;
 mod supply;
 mod unrealized;
+mod urpd;
 mod utxo;
 
 pub use input::*;
 pub use output::*;
 pub use price_paid::*;
+pub use ratios::*;
 pub use realized::*;
+pub use sopr::*;
 pub use supply::*;
 pub use unrealized::*;
+pub use urpd::*;
 pub use utxo::*;
 
 use crate::datasets::AnyDataset;
@@ -22,9 +28,11 @@ pub struct SubDataset {
     pub input: InputSubDataset,
     pub output: OutputSubDataset,
     pub price_paid: PricePaidSubDataset,
+    pub ratios: RatiosSubDataset,
     pub realized: RealizedSubDataset,
     pub supply: SupplySubDataset,
     pub unrealized: UnrealizedSubDataset,
+    pub urpd: URPDSubDataset,
     pub utxo: UTXOSubDataset,
 }
 
@@ -34,9 +42,11 @@ impl SubDataset {
             input: InputSubDataset::import(parent_path)?,
             output: OutputSubDataset::import(parent_path)?,
             price_paid: PricePaidSubDataset::import(parent_path)?,
+            ratios: RatiosSubDataset::import(parent_path)?,
             realized: RealizedSubDataset::import(parent_path)?,
             supply: SupplySubDataset::import(parent_path)?,
             unrealized: UnrealizedSubDataset::import(parent_path)?,
+            urpd: URPDSubDataset::import(parent_path)?,
             utxo: UTXOSubDataset::import(parent_path)?,
         };
 
@@ -48,9 +58,11 @@ impl AnyDatasetGroup for SubDataset {
     fn as_vec(&self) -> Vec<&(dyn AnyDataset + Send + Sync)> {
         vec![
             &self.price_paid,
+            &self.ratios,
             &self.realized,
             &self.supply,
             &self.unrealized,
+            &self.urpd,
             &self.utxo,
             &self.input,
             &self.output,
@@ -60,9 +72,11 @@ impl AnyDatasetGroup for SubDataset {
     fn as_mut_vec(&mut self) -> Vec<&mut dyn AnyDataset> {
         vec![
             &mut self.price_paid,
+            &mut self.ratios,
             &mut self.realized,
             &mut self.supply,
             &mut self.unrealized,
+            &mut self.urpd,
             &mut self.utxo,
             &mut self.input,
             &mut self.output,
@@ -1,16 +1,15 @@
 use chrono::NaiveDate;
-use itertools::Itertools;
 
 use crate::{
     datasets::{AnyDataset, MinInitialState, ProcessedBlockData},
-    parse::{AnyBiMap, BiMap},
+    parse::{AnyBiMap, AnyDollarBiMap, BiMap},
     states::PricePaidState,
 };
 
 pub struct PricePaidSubDataset {
     min_initial_state: MinInitialState,
 
-    pub realized_cap: BiMap<f32>,
+    pub realized_cap: BiMap<f64>,
     pub realized_price: BiMap<f32>,
 
     pp_median: BiMap<f32>,
@@ -41,7 +40,7 @@ impl PricePaidSubDataset {
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
 
-            realized_cap: BiMap::new_bin(1, &f("realized_cap")),
+            realized_cap: BiMap::new_bin(2, &f("realized_cap")),
             realized_price: BiMap::new_bin(1, &f("realized_price")),
 
             pp_median: BiMap::new_bin(1, &f("median_price_paid")),
@@ -115,7 +114,7 @@ impl PricePaidSubDataset {
         let realized_price = self
             .realized_price
             .height
-            .insert(height, cohort_supply / realized_cap);
+            .insert(height, (cohort_supply as f64 / realized_cap) as f32);
 
         if is_date_last_block {
             self.realized_price.date.insert(date, realized_price);
@@ -176,18 +175,54 @@ impl PricePaidSubDataset {
     }
 
     fn insert_height_default(&mut self, height: usize) {
+        self.realized_cap.height.insert_default(height);
+
         self.as_mut_vec().into_iter().for_each(|bi| {
             bi.height.insert_default(height);
         })
     }
 
     fn insert_date_default(&mut self, date: NaiveDate) {
+        self.realized_cap.date.insert_default(date);
+
         self.as_mut_vec().into_iter().for_each(|bi| {
             bi.date.insert_default(date);
         })
     }
 
-    pub fn as_vec(&self) -> Vec<&BiMap<f32>> {
+    /// Every map but `realized_cap`, which is an f64
+    pub fn as_mut_vec(&mut self) -> Vec<&mut BiMap<f32>> {
+        vec![
+            &mut self.realized_price,
+            &mut self.pp_95p,
+            &mut self.pp_90p,
+            &mut self.pp_85p,
+            &mut self.pp_80p,
+            &mut self.pp_75p,
+            &mut self.pp_70p,
+            &mut self.pp_65p,
+            &mut self.pp_60p,
+            &mut self.pp_55p,
+            &mut self.pp_median,
+            &mut self.pp_45p,
+            &mut self.pp_40p,
+            &mut self.pp_35p,
+            &mut self.pp_30p,
+            &mut self.pp_25p,
+            &mut self.pp_20p,
+            &mut self.pp_15p,
+            &mut self.pp_10p,
+            &mut self.pp_05p,
+        ]
+    }
+}
+
+impl AnyDataset for PricePaidSubDataset {
+    fn get_min_initial_state(&self) -> &MinInitialState {
+        &self.min_initial_state
+    }
+
+    fn to_any_bi_map_vec(&self) -> Vec<&(dyn AnyBiMap + Send + Sync)> {
         vec![
             &self.realized_cap,
             &self.realized_price,
@@ -213,7 +248,7 @@ impl PricePaidSubDataset {
         ]
     }
 
-    pub fn as_mut_vec(&mut self) -> Vec<&mut BiMap<f32>> {
+    fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
         vec![
             &mut self.realized_cap,
             &mut self.realized_price,
@@ -238,24 +273,30 @@ impl PricePaidSubDataset {
             &mut self.pp_05p,
         ]
     }
-}
 
-impl AnyDataset for PricePaidSubDataset {
-    fn get_min_initial_state(&self) -> &MinInitialState {
-        &self.min_initial_state
-    }
-
-    fn to_any_bi_map_vec(&self) -> Vec<&(dyn AnyBiMap + Send + Sync)> {
-        self.as_vec()
-            .into_iter()
-            .map(|dataset| dataset as &(dyn AnyBiMap + Send + Sync))
-            .collect_vec()
-    }
-
-    fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
-        self.as_mut_vec()
-            .into_iter()
-            .map(|dataset| dataset as &mut dyn AnyBiMap)
-            .collect_vec()
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![
+            &self.realized_cap,
+            &self.realized_price,
+            &self.pp_95p,
+            &self.pp_90p,
+            &self.pp_85p,
+            &self.pp_80p,
+            &self.pp_75p,
+            &self.pp_70p,
+            &self.pp_65p,
+            &self.pp_60p,
+            &self.pp_55p,
+            &self.pp_median,
+            &self.pp_45p,
+            &self.pp_40p,
+            &self.pp_35p,
+            &self.pp_30p,
+            &self.pp_25p,
+            &self.pp_20p,
+            &self.pp_15p,
+            &self.pp_10p,
+            &self.pp_05p,
+        ]
     }
 }
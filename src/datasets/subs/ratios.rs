@@ -0,0 +1,395 @@
+use chrono::NaiveDate;
+
+use crate::{
+    bitcoin::sats_to_btc_f64,
+    datasets::{AnyDataset, MinInitialState, ProcessedBlockData},
+    parse::{AnyBiMap, AnyDollarBiMap, AnyHeightMap, BiMap, HeightMap},
+    states::{OneShotStates, SupplyState, UnrealizedState},
+};
+
+///
+/// Ratios between the market cap of the cohort (its supply at the current price), its realized cap and its unrealized profit and loss.
+///
+/// The MVRV is also the ratio between the price and the realized price, its bands being the prices at its mean and standard deviations over the history of the cohort.
+///
+pub struct RatiosSubDataset {
+    min_initial_state: MinInitialState,
+
+    market_cap: BiMap<f64>,
+    mvrv: BiMap<f32>,
+    mvrv_z_score: BiMap<f32>,
+    nupl: BiMap<f32>,
+    relative_unrealized_profit: BiMap<f32>,
+    relative_unrealized_loss: BiMap<f32>,
+
+    realized_price_band_mean: BiMap<f32>,
+    realized_price_band_p1sd: BiMap<f32>,
+    realized_price_band_p2sd: BiMap<f32>,
+    realized_price_band_p3sd: BiMap<f32>,
+    realized_price_band_m1sd: BiMap<f32>,
+    realized_price_band_m2sd: BiMap<f32>,
+    realized_price_band_m3sd: BiMap<f32>,
+
+    // Values at each height summed below, 0 when the cohort has no supply
+    has_supply: HeightMap<f64>,
+    market_cap_squared: HeightMap<f64>,
+    exact_mvrv: HeightMap<f64>,
+    mvrv_squared: HeightMap<f64>,
+
+    // Sums over the heights where the cohort had a supply, for the means and standard deviations
+    supplied_heights: HeightMap<f64>,
+    market_cap_sum: HeightMap<f64>,
+    market_cap_squared_sum: HeightMap<f64>,
+    mvrv_sum: HeightMap<f64>,
+    mvrv_squared_sum: HeightMap<f64>,
+}
+
+impl RatiosSubDataset {
+    pub fn import(parent_path: &str) -> color_eyre::Result<Self> {
+        let f = |s: &str| format!("{parent_path}/{s}");
+
+        let mut s = Self {
+            min_initial_state: MinInitialState::default(),
+
+            market_cap: BiMap::new_bin(1, &f("market_cap")),
+            mvrv: BiMap::new_bin(1, &f("mvrv")),
+            mvrv_z_score: BiMap::new_bin(1, &f("mvrv_z_score")),
+            nupl: BiMap::new_bin(1, &f("nupl")),
+            relative_unrealized_profit: BiMap::new_bin(1, &f("relative_unrealized_profit")),
+            relative_unrealized_loss: BiMap::new_bin(1, &f("relative_unrealized_loss")),
+
+            realized_price_band_mean: BiMap::new_bin(1, &f("realized_price_band_mean")),
+            realized_price_band_p1sd: BiMap::new_bin(1, &f("realized_price_band_p1sd")),
+            realized_price_band_p2sd: BiMap::new_bin(1, &f("realized_price_band_p2sd")),
+            realized_price_band_p3sd: BiMap::new_bin(1, &f("realized_price_band_p3sd")),
+            realized_price_band_m1sd: BiMap::new_bin(1, &f("realized_price_band_m1sd")),
+            realized_price_band_m2sd: BiMap::new_bin(1, &f("realized_price_band_m2sd")),
+            realized_price_band_m3sd: BiMap::new_bin(1, &f("realized_price_band_m3sd")),
+
+            has_supply: HeightMap::new_bin(1, &f("has_supply")),
+            market_cap_squared: HeightMap::new_bin(1, &f("market_cap_squared")),
+            exact_mvrv: HeightMap::new_bin(1, &f("exact_mvrv")),
+            mvrv_squared: HeightMap::new_bin(1, &f("mvrv_squared")),
+
+            supplied_heights: HeightMap::new_bin(1, &f("supplied_heights")),
+            market_cap_sum: HeightMap::new_bin(1, &f("market_cap_sum")),
+            market_cap_squared_sum: HeightMap::new_bin(1, &f("market_cap_squared_sum")),
+            mvrv_sum: HeightMap::new_bin(1, &f("mvrv_sum")),
+            mvrv_squared_sum: HeightMap::new_bin(1, &f("mvrv_squared_sum")),
+        };
+
+        s.min_initial_state
+            .consume(MinInitialState::compute_from_dataset(&s));
+
+        Ok(s)
+    }
+
+    pub fn insert(
+        &mut self,
+        &ProcessedBlockData {
+            height,
+            date,
+            is_date_last_block,
+            block_price,
+            date_price,
+            ..
+        }: &ProcessedBlockData,
+        supply_state: &SupplyState,
+        one_shot_states: &OneShotStates,
+    ) {
+        let supply = sats_to_btc_f64(supply_state.supply);
+        let realized_cap = one_shot_states.price_paid_state.realized_cap;
+
+        let market_cap = supply * block_price as f64;
+        let mvrv = divide(market_cap, realized_cap);
+
+        let has_supply = realized_cap > 0.0;
+
+        self.has_supply.insert(height, has_supply as u8 as f64);
+        self.market_cap.height.insert(height, market_cap);
+        self.market_cap_squared.insert(height, market_cap.powi(2));
+        self.exact_mvrv.insert(height, mvrv);
+        self.mvrv_squared.insert(height, mvrv.powi(2));
+
+        let count = self
+            .supplied_heights
+            .insert_cumulative(height, &self.has_supply);
+        let market_cap_sum = self
+            .market_cap_sum
+            .insert_cumulative(height, &self.market_cap.height);
+        let market_cap_squared_sum = self
+            .market_cap_squared_sum
+            .insert_cumulative(height, &self.market_cap_squared);
+        let mvrv_sum = self.mvrv_sum.insert_cumulative(height, &self.exact_mvrv);
+        let mvrv_squared_sum = self
+            .mvrv_squared_sum
+            .insert_cumulative(height, &self.mvrv_squared);
+
+        let market_cap_sd = standard_deviation(count, market_cap_sum, market_cap_squared_sum);
+        let mvrv_mean = divide(mvrv_sum, count);
+        let mvrv_sd = standard_deviation(count, mvrv_sum, mvrv_squared_sum);
+
+        let realized_price = divide(realized_cap, supply);
+
+        self.insert_height(
+            height,
+            market_cap,
+            realized_cap,
+            market_cap_sd,
+            &one_shot_states.unrealized_block_state,
+        );
+
+        self.insert_bands_height(height, realized_price, mvrv_mean, mvrv_sd);
+
+        if is_date_last_block {
+            self.insert_date(
+                date,
+                supply * date_price as f64,
+                realized_cap,
+                market_cap_sd,
+                one_shot_states.unrealized_date_state.as_ref().unwrap(),
+            );
+
+            self.insert_bands_date(date, realized_price, mvrv_mean, mvrv_sd);
+        }
+    }
+
+    fn insert_height(
+        &mut self,
+        height: usize,
+        market_cap: f64,
+        realized_cap: f64,
+        market_cap_sd: f64,
+        state: &UnrealizedState,
+    ) {
+        self.mvrv
+            .height
+            .insert(height, divide(market_cap, realized_cap) as f32);
+
+        self.mvrv_z_score.height.insert(
+            height,
+            divide(market_cap - realized_cap, market_cap_sd) as f32,
+        );
+
+        self.nupl.height.insert(
+            height,
+            divide(state.unrealized_profit - state.unrealized_loss, market_cap) as f32,
+        );
+
+        self.relative_unrealized_profit
+            .height
+            .insert(height, divide(state.unrealized_profit, market_cap) as f32);
+
+        self.relative_unrealized_loss
+            .height
+            .insert(height, divide(state.unrealized_loss, market_cap) as f32);
+    }
+
+    fn insert_date(
+        &mut self,
+        date: NaiveDate,
+        market_cap: f64,
+        realized_cap: f64,
+        market_cap_sd: f64,
+        state: &UnrealizedState,
+    ) {
+        self.market_cap.date.insert(date, market_cap);
+
+        self.mvrv
+            .date
+            .insert(date, divide(market_cap, realized_cap) as f32);
+
+        self.mvrv_z_score.date.insert(
+            date,
+            divide(market_cap - realized_cap, market_cap_sd) as f32,
+        );
+
+        self.nupl.date.insert(
+            date,
+            divide(state.unrealized_profit - state.unrealized_loss, market_cap) as f32,
+        );
+
+        self.relative_unrealized_profit
+            .date
+            .insert(date, divide(state.unrealized_profit, market_cap) as f32);
+
+        self.relative_unrealized_loss
+            .date
+            .insert(date, divide(state.unrealized_loss, market_cap) as f32);
+    }
+
+    fn insert_bands_height(&mut self, height: usize, realized_price: f64, mean: f64, sd: f64) {
+        self.bands_mut().into_iter().for_each(|(sds, band)| {
+            band.height
+                .insert(height, band_price(realized_price, mean, sd, sds) as f32);
+        });
+    }
+
+    fn insert_bands_date(&mut self, date: NaiveDate, realized_price: f64, mean: f64, sd: f64) {
+        self.bands_mut().into_iter().for_each(|(sds, band)| {
+            band.date
+                .insert(date, band_price(realized_price, mean, sd, sds) as f32);
+        });
+    }
+
+    /// By their number of standard deviations from the mean
+    fn bands_mut(&mut self) -> [(f64, &mut BiMap<f32>); 7] {
+        [
+            (0.0, &mut self.realized_price_band_mean),
+            (1.0, &mut self.realized_price_band_p1sd),
+            (2.0, &mut self.realized_price_band_p2sd),
+            (3.0, &mut self.realized_price_band_p3sd),
+            (-1.0, &mut self.realized_price_band_m1sd),
+            (-2.0, &mut self.realized_price_band_m2sd),
+            (-3.0, &mut self.realized_price_band_m3sd),
+        ]
+    }
+}
+
+/// Zero when there's nothing to divide by, like before the cohort has a supply
+fn divide(numerator: f64, denominator: f64) -> f64 {
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+fn standard_deviation(count: f64, sum: f64, squared_sum: f64) -> f64 {
+    let mean = divide(sum, count);
+
+    (divide(squared_sum, count) - mean.powi(2)).max(0.0).sqrt()
+}
+
+/// Price at which the MVRV is `sds` standard deviations away from its mean
+fn band_price(realized_price: f64, mvrv_mean: f64, mvrv_sd: f64, sds: f64) -> f64 {
+    realized_price * (mvrv_mean + sds * mvrv_sd)
+}
+
+impl AnyDataset for RatiosSubDataset {
+    fn get_min_initial_state(&self) -> &MinInitialState {
+        &self.min_initial_state
+    }
+
+    fn to_any_height_map_vec(&self) -> Vec<&(dyn AnyHeightMap + Send + Sync)> {
+        vec![
+            &self.has_supply,
+            &self.market_cap_squared,
+            &self.exact_mvrv,
+            &self.mvrv_squared,
+            &self.supplied_heights,
+            &self.market_cap_sum,
+            &self.market_cap_squared_sum,
+            &self.mvrv_sum,
+            &self.mvrv_squared_sum,
+        ]
+    }
+
+    fn to_any_mut_height_map_vec(&mut self) -> Vec<&mut dyn AnyHeightMap> {
+        vec![
+            &mut self.has_supply,
+            &mut self.market_cap_squared,
+            &mut self.exact_mvrv,
+            &mut self.mvrv_squared,
+            &mut self.supplied_heights,
+            &mut self.market_cap_sum,
+            &mut self.market_cap_squared_sum,
+            &mut self.mvrv_sum,
+            &mut self.mvrv_squared_sum,
+        ]
+    }
+
+    fn to_any_bi_map_vec(&self) -> Vec<&(dyn AnyBiMap + Send + Sync)> {
+        vec![
+            &self.market_cap,
+            &self.mvrv,
+            &self.mvrv_z_score,
+            &self.nupl,
+            &self.relative_unrealized_profit,
+            &self.relative_unrealized_loss,
+            &self.realized_price_band_mean,
+            &self.realized_price_band_p1sd,
+            &self.realized_price_band_p2sd,
+            &self.realized_price_band_p3sd,
+            &self.realized_price_band_m1sd,
+            &self.realized_price_band_m2sd,
+            &self.realized_price_band_m3sd,
+        ]
+    }
+
+    fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
+        vec![
+            &mut self.market_cap,
+            &mut self.mvrv,
+            &mut self.mvrv_z_score,
+            &mut self.nupl,
+            &mut self.relative_unrealized_profit,
+            &mut self.relative_unrealized_loss,
+            &mut self.realized_price_band_mean,
+            &mut self.realized_price_band_p1sd,
+            &mut self.realized_price_band_p2sd,
+            &mut self.realized_price_band_p3sd,
+            &mut self.realized_price_band_m1sd,
+            &mut self.realized_price_band_m2sd,
+            &mut self.realized_price_band_m3sd,
+        ]
+    }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![
+            &self.market_cap,
+            &self.realized_price_band_mean,
+            &self.realized_price_band_p1sd,
+            &self.realized_price_band_p2sd,
+            &self.realized_price_band_p3sd,
+            &self.realized_price_band_m1sd,
+            &self.realized_price_band_m2sd,
+            &self.realized_price_band_m3sd,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_z_score_and_bands() {
+        // Market caps with a mean of 5 and a standard deviation of 2
+        let market_caps = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+
+        let sd = standard_deviation(
+            market_caps.len() as f64,
+            market_caps.iter().sum(),
+            market_caps.iter().map(|value| value * value).sum(),
+        );
+
+        assert_eq!(sd, 2.0);
+
+        // Market cap of 9 for a realized cap of 5
+        assert_eq!(divide(9.0 - 5.0, sd), 2.0);
+
+        // Nothing yet
+        assert_eq!(standard_deviation(0.0, 0.0, 0.0), 0.0);
+        assert_eq!(divide(1.0, 0.0), 0.0);
+
+        // MVRVs of 1, 1.5 and 2 at a realized price of 10
+        let mvrvs = [1.0, 1.5, 2.0];
+
+        let mean = divide(mvrvs.iter().sum(), mvrvs.len() as f64);
+        let sd = standard_deviation(
+            mvrvs.len() as f64,
+            mvrvs.iter().sum(),
+            mvrvs.iter().map(|value| value * value).sum(),
+        );
+
+        assert_eq!(band_price(10.0, mean, sd, 0.0), 15.0);
+        assert!(
+            (band_price(10.0, mean, sd, 1.0) - (15.0 + 10.0 * (1.0_f64 / 6.0).sqrt())).abs() < 1e-9
+        );
+        assert!(
+            (band_price(10.0, mean, sd, -2.0) - (15.0 - 20.0 * (1.0_f64 / 6.0).sqrt())).abs()
+                < 1e-9
+        );
+    }
+}
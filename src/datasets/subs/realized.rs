@@ -1,6 +1,6 @@
 use crate::{
     datasets::{AnyDataset, MinInitialState, ProcessedBlockData},
-    parse::{AnyBiMap, BiMap},
+    parse::{AnyBiMap, AnyDollarBiMap, BiMap},
     states::RealizedState,
 };
 
@@ -8,8 +8,8 @@ use crate::{
 pub struct RealizedSubDataset {
     min_initial_state: MinInitialState,
 
-    realized_profit: BiMap<f32>,
-    realized_loss: BiMap<f32>,
+    realized_profit: BiMap<f64>,
+    realized_loss: BiMap<f64>,
 }
 
 impl RealizedSubDataset {
@@ -19,8 +19,8 @@ impl RealizedSubDataset {
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
 
-            realized_profit: BiMap::new_bin(1, &f("realized_profit")),
-            realized_loss: BiMap::new_bin(1, &f("realized_loss")),
+            realized_profit: BiMap::new_bin(2, &f("realized_profit")),
+            realized_loss: BiMap::new_bin(2, &f("realized_loss")),
         };
 
         s.min_initial_state
@@ -70,4 +70,8 @@ impl AnyDataset for RealizedSubDataset {
     fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
         vec![&mut self.realized_loss, &mut self.realized_profit]
     }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![&self.realized_profit, &self.realized_loss]
+    }
 }
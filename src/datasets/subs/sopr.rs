@@ -0,0 +1,168 @@
+use crate::{
+    datasets::{AnyDataset, MinInitialState, ProcessedBlockData},
+    parse::{AnyBiMap, AnyDollarBiMap, BiMap},
+    states::SOPRState,
+};
+
+///
+/// Spent Output Profit Ratio, the value of the spent outputs at their spend divided by their value at their creation.
+///
+/// The adjusted one leaves out the outputs younger than an hour.
+///
+pub struct SOPRSubDataset {
+    min_initial_state: MinInitialState,
+
+    spent_value: BiMap<f64>,
+    spent_value_at_creation: BiMap<f64>,
+    adjusted_spent_value: BiMap<f64>,
+    adjusted_spent_value_at_creation: BiMap<f64>,
+
+    sopr: BiMap<f32>,
+    adjusted_sopr: BiMap<f32>,
+}
+
+impl SOPRSubDataset {
+    pub fn import(parent_path: &str) -> color_eyre::Result<Self> {
+        let f = |s: &str| format!("{parent_path}/{s}");
+
+        let mut s = Self {
+            min_initial_state: MinInitialState::default(),
+
+            spent_value: BiMap::new_bin(1, &f("spent_value")),
+            spent_value_at_creation: BiMap::new_bin(1, &f("spent_value_at_creation")),
+            adjusted_spent_value: BiMap::new_bin(1, &f("adjusted_spent_value")),
+            adjusted_spent_value_at_creation: BiMap::new_bin(
+                1,
+                &f("adjusted_spent_value_at_creation"),
+            ),
+
+            sopr: BiMap::new_bin(1, &f("sopr")),
+            adjusted_sopr: BiMap::new_bin(1, &f("adjusted_sopr")),
+        };
+
+        s.min_initial_state
+            .consume(MinInitialState::compute_from_dataset(&s));
+
+        Ok(s)
+    }
+
+    pub fn insert(
+        &mut self,
+        &ProcessedBlockData {
+            height,
+            date,
+            is_date_last_block,
+            date_blocks_range,
+            ..
+        }: &ProcessedBlockData,
+        height_state: &SOPRState,
+    ) {
+        let SOPRState {
+            spent_value,
+            spent_value_at_creation,
+            adjusted_spent_value,
+            adjusted_spent_value_at_creation,
+        } = *height_state;
+
+        self.spent_value.height.insert(height, spent_value);
+        self.spent_value_at_creation
+            .height
+            .insert(height, spent_value_at_creation);
+        self.adjusted_spent_value
+            .height
+            .insert(height, adjusted_spent_value);
+        self.adjusted_spent_value_at_creation
+            .height
+            .insert(height, adjusted_spent_value_at_creation);
+
+        self.sopr
+            .height
+            .insert(height, ratio(spent_value, spent_value_at_creation));
+        self.adjusted_sopr.height.insert(
+            height,
+            ratio(adjusted_spent_value, adjusted_spent_value_at_creation),
+        );
+
+        if is_date_last_block {
+            let spent_value = self.spent_value.height.sum_range(date_blocks_range);
+            let spent_value_at_creation = self
+                .spent_value_at_creation
+                .height
+                .sum_range(date_blocks_range);
+            let adjusted_spent_value = self
+                .adjusted_spent_value
+                .height
+                .sum_range(date_blocks_range);
+            let adjusted_spent_value_at_creation = self
+                .adjusted_spent_value_at_creation
+                .height
+                .sum_range(date_blocks_range);
+
+            self.spent_value.date.insert(date, spent_value);
+            self.spent_value_at_creation
+                .date
+                .insert(date, spent_value_at_creation);
+            self.adjusted_spent_value
+                .date
+                .insert(date, adjusted_spent_value);
+            self.adjusted_spent_value_at_creation
+                .date
+                .insert(date, adjusted_spent_value_at_creation);
+
+            self.sopr
+                .date
+                .insert(date, ratio(spent_value, spent_value_at_creation));
+
+            self.adjusted_sopr.date.insert(
+                date,
+                ratio(adjusted_spent_value, adjusted_spent_value_at_creation),
+            );
+        }
+    }
+}
+
+/// Without any spent value, there's neither profit nor loss
+fn ratio(spent_value: f64, spent_value_at_creation: f64) -> f32 {
+    if spent_value_at_creation == 0.0 {
+        1.0
+    } else {
+        (spent_value / spent_value_at_creation) as f32
+    }
+}
+
+impl AnyDataset for SOPRSubDataset {
+    fn get_min_initial_state(&self) -> &MinInitialState {
+        &self.min_initial_state
+    }
+
+    fn to_any_bi_map_vec(&self) -> Vec<&(dyn AnyBiMap + Send + Sync)> {
+        vec![
+            &self.spent_value,
+            &self.spent_value_at_creation,
+            &self.adjusted_spent_value,
+            &self.adjusted_spent_value_at_creation,
+            &self.sopr,
+            &self.adjusted_sopr,
+        ]
+    }
+
+    fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
+        vec![
+            &mut self.spent_value,
+            &mut self.spent_value_at_creation,
+            &mut self.adjusted_spent_value,
+            &mut self.adjusted_spent_value_at_creation,
+            &mut self.sopr,
+            &mut self.adjusted_sopr,
+        ]
+    }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![
+            &self.spent_value,
+            &self.spent_value_at_creation,
+            &self.adjusted_spent_value,
+            &self.adjusted_spent_value_at_creation,
+        ]
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     bitcoin::sats_to_btc,
     datasets::{AnyDataset, MinInitialState, ProcessedBlockData},
-    parse::{AnyBiMap, BiMap},
+    parse::{AnyBiMap, AnyDollarBiMap, BiMap},
     states::UnrealizedState,
 };
 
@@ -9,8 +9,8 @@ pub struct UnrealizedSubDataset {
     min_initial_state: MinInitialState,
 
     supply_in_profit: BiMap<f32>,
-    unrealized_profit: BiMap<f32>,
-    unrealized_loss: BiMap<f32>,
+    unrealized_profit: BiMap<f64>,
+    unrealized_loss: BiMap<f64>,
 }
 
 impl UnrealizedSubDataset {
@@ -21,8 +21,8 @@ impl UnrealizedSubDataset {
             min_initial_state: MinInitialState::default(),
 
             supply_in_profit: BiMap::new_bin(1, &f("supply_in_profit")),
-            unrealized_profit: BiMap::new_bin(1, &f("unrealized_profit")),
-            unrealized_loss: BiMap::new_bin(1, &f("unrealized_loss")),
+            unrealized_profit: BiMap::new_bin(2, &f("unrealized_profit")),
+            unrealized_loss: BiMap::new_bin(2, &f("unrealized_loss")),
         };
 
         s.min_initial_state
@@ -92,4 +92,8 @@ impl AnyDataset for UnrealizedSubDataset {
             &mut self.unrealized_loss,
         ]
     }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        vec![&self.unrealized_profit, &self.unrealized_loss]
+    }
 }
@@ -0,0 +1,107 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use chrono::NaiveDate;
+use rayon::prelude::*;
+
+use crate::{
+    datasets::{AnyDataset, MinInitialState},
+    io::Json,
+    states::URPDState,
+};
+
+///
+/// Not made of maps, the distribution of each date is exported to `{parent_path}/urpd/{date}.json`.
+///
+/// Distributions are kept in memory until the datasets are exported and like the values of maps, they're removed
+/// when the datasets are reset or rolled back.
+///
+pub struct URPDSubDataset {
+    min_initial_state: MinInitialState,
+
+    path: String,
+    to_export: BTreeMap<NaiveDate, URPDState>,
+}
+
+impl URPDSubDataset {
+    pub fn import(parent_path: &str) -> color_eyre::Result<Self> {
+        Ok(Self {
+            min_initial_state: MinInitialState::default(),
+
+            path: format!("{parent_path}/urpd"),
+            to_export: BTreeMap::new(),
+        })
+    }
+
+    pub fn insert(&mut self, date: NaiveDate, urpd: URPDState) {
+        self.to_export.insert(date, urpd);
+    }
+
+    fn date_path(&self, date: NaiveDate) -> String {
+        format!("{}/{date}.json", self.path)
+    }
+
+    /// Dates of the distributions already exported
+    fn read_dir(&self) -> color_eyre::Result<Vec<NaiveDate>> {
+        if !Path::new(&self.path).exists() {
+            return Ok(vec![]);
+        }
+
+        let mut dates = vec![];
+
+        for entry in fs::read_dir(&self.path)? {
+            if let Some(date) = entry?
+                .file_name()
+                .to_str()
+                .and_then(|file_name| file_name.strip_suffix(".json"))
+                .and_then(|stem| stem.parse::<NaiveDate>().ok())
+            {
+                dates.push(date);
+            }
+        }
+
+        Ok(dates)
+    }
+}
+
+impl AnyDataset for URPDSubDataset {
+    fn get_min_initial_state(&self) -> &MinInitialState {
+        &self.min_initial_state
+    }
+
+    fn export(&self) -> color_eyre::Result<()> {
+        if self.to_export.is_empty() {
+            return Ok(());
+        }
+
+        fs::create_dir_all(&self.path)?;
+
+        self.to_export
+            .par_iter()
+            .try_for_each(|(date, urpd)| Json::export(&self.date_path(*date), &urpd.to_snapshot()))
+    }
+
+    fn post_export(&mut self) {
+        self.to_export.clear();
+    }
+
+    fn reset(&mut self) -> color_eyre::Result<()> {
+        self.to_export.clear();
+
+        if Path::new(&self.path).exists() {
+            fs::remove_dir_all(&self.path)?;
+        }
+
+        Ok(())
+    }
+
+    fn rollback(&mut self, _: usize, date: NaiveDate) -> color_eyre::Result<()> {
+        self.to_export.retain(|urpd_date, _| *urpd_date < date);
+
+        self.read_dir()?
+            .into_iter()
+            .filter(|urpd_date| *urpd_date >= date)
+            .try_for_each(|urpd_date| fs::remove_file(self.date_path(urpd_date)))?;
+
+        Ok(())
+    }
+}
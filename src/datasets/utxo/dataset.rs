@@ -1,9 +1,12 @@
 use itertools::Itertools;
 
 use crate::{
-    datasets::{AnyDataset, AnyDatasetGroup, MinInitialState, ProcessedBlockData, SubDataset},
-    parse::{AnyBiMap, AnyDateMap, AnyHeightMap},
-    states::UTXOCohortId,
+    datasets::{
+        AnyDataset, AnyDatasetGroup, MinInitialState, ProcessedBlockData, SOPRSubDataset,
+        SubDataset,
+    },
+    parse::{AnyBiMap, AnyDateMap, AnyDollarBiMap, AnyHeightMap},
+    states::{DurableStates, OneShotStates, OutputState, SentState, UTXOCohortId},
 };
 
 pub struct UTXODataset {
@@ -11,18 +14,19 @@ pub struct UTXODataset {
     id: UTXOCohortId,
 
     pub subs: SubDataset,
+    /// Only UTXO cohorts know the price at which their spent outputs were created
+    pub sopr: SOPRSubDataset,
 }
 
 impl UTXODataset {
     pub fn import(parent_path: &str, id: UTXOCohortId) -> color_eyre::Result<Self> {
-        let name = id.name();
-
-        let folder_path = format!("{parent_path}/{name}");
+        let folder_path = Self::folder_path(parent_path, id);
 
         let mut s = Self {
             min_initial_state: MinInitialState::default(),
             id,
             subs: SubDataset::import(&folder_path)?,
+            sopr: SOPRSubDataset::import(&folder_path)?,
         };
 
         s.min_initial_state
@@ -31,7 +35,40 @@ impl UTXODataset {
         Ok(s)
     }
 
-    pub fn insert_data(&mut self, processed_block_data: &ProcessedBlockData) {
+    pub fn id(&self) -> UTXOCohortId {
+        self.id
+    }
+
+    /// The supply is always inserted, a cohort without it was never computed
+    pub fn has_values(&self) -> bool {
+        self.subs
+            .supply
+            .total
+            .height
+            .get_initial_last_height()
+            .is_some()
+    }
+
+    pub fn folder_path(parent_path: &str, id: UTXOCohortId) -> String {
+        format!("{parent_path}/{}", id.name())
+    }
+
+    fn as_vec(&self) -> Vec<&(dyn AnyDataset + Send + Sync)> {
+        let mut vec = self.subs.as_vec();
+        vec.push(&self.sopr);
+        vec
+    }
+
+    fn as_mut_vec(&mut self) -> Vec<&mut dyn AnyDataset> {
+        let mut vec = self.subs.as_mut_vec();
+        vec.push(&mut self.sopr);
+        vec
+    }
+
+    pub fn insert_data(
+        &mut self,
+        processed_block_data: &ProcessedBlockData,
+    ) -> color_eyre::Result<()> {
         let &ProcessedBlockData {
             date,
             height,
@@ -42,63 +79,89 @@ impl UTXODataset {
             ..
         } = processed_block_data;
 
+        // Year and epoch cohorts only have states once outputs were created during them
+        let default_durable_states = DurableStates::default();
+        let default_one_shot_states = OneShotStates::default();
+        let default_sent_state = SentState::default();
+        let default_received_state = OutputState::default();
+
+        let durable_states = states
+            .utxo_cohorts_durable_states
+            .get(&self.id)
+            .unwrap_or(&default_durable_states);
+        let one_shot_states = utxo_cohorts_one_shot_states
+            .get(&self.id)
+            .unwrap_or(&default_one_shot_states);
+        let sent_state = utxo_cohorts_sent_states
+            .get(&self.id)
+            .unwrap_or(&default_sent_state);
+        let received_state = utxo_cohorts_received_states
+            .get(&self.id)
+            .unwrap_or(&default_received_state);
+
         if self.subs.supply.should_insert(height, date) {
-            self.subs.supply.insert(
-                processed_block_data,
-                &states
-                    .utxo_cohorts_durable_states
-                    .get(&self.id)
-                    .supply_state,
-            );
+            self.subs
+                .supply
+                .insert(processed_block_data, &durable_states.supply_state);
         }
 
         if self.subs.utxo.should_insert(height, date) {
-            self.subs.utxo.insert(
-                processed_block_data,
-                &states.utxo_cohorts_durable_states.get(&self.id).utxo_state,
-            );
+            self.subs
+                .utxo
+                .insert(processed_block_data, &durable_states.utxo_state);
         }
 
         if self.subs.unrealized.should_insert(height, date) {
             self.subs.unrealized.insert(
                 processed_block_data,
-                &utxo_cohorts_one_shot_states
-                    .get(&self.id)
-                    .unrealized_block_state,
-                &utxo_cohorts_one_shot_states
-                    .get(&self.id)
-                    .unrealized_date_state,
+                &one_shot_states.unrealized_block_state,
+                &one_shot_states.unrealized_date_state,
             );
         }
 
         if self.subs.price_paid.should_insert(height, date) {
             self.subs.price_paid.insert(
                 processed_block_data,
-                &utxo_cohorts_one_shot_states.get(&self.id).price_paid_state,
+                &one_shot_states.price_paid_state,
                 self.subs.supply.total.height.get(&height).unwrap(),
             );
         }
 
-        if self.subs.realized.should_insert(height, date) {
-            self.subs.realized.insert(
+        if self.subs.ratios.should_insert(height, date) {
+            self.subs.ratios.insert(
                 processed_block_data,
-                &utxo_cohorts_sent_states.get(&self.id).realized,
+                &durable_states.supply_state,
+                one_shot_states,
             );
         }
 
+        if self.subs.realized.should_insert(height, date) {
+            self.subs
+                .realized
+                .insert(processed_block_data, &sent_state.realized);
+        }
+
+        if self.sopr.should_insert(height, date) {
+            self.sopr.insert(processed_block_data, &sent_state.sopr);
+        }
+
         if self.subs.input.should_insert(height, date) {
-            self.subs.input.insert(
-                processed_block_data,
-                &utxo_cohorts_sent_states.get(&self.id).input,
-            );
+            self.subs
+                .input
+                .insert(processed_block_data, &sent_state.input);
         }
 
         if self.subs.output.should_insert(height, date) {
-            self.subs.output.insert(
-                processed_block_data,
-                utxo_cohorts_received_states.get(&self.id),
-            );
+            self.subs
+                .output
+                .insert(processed_block_data, received_state);
         }
+
+        if let Some(urpd) = one_shot_states.urpd.as_ref() {
+            self.subs.urpd.insert(date, urpd.clone());
+        }
+
+        Ok(())
     }
 }
 
@@ -108,50 +171,51 @@ impl AnyDataset for UTXODataset {
     }
 
     fn to_any_height_map_vec(&self) -> Vec<&(dyn AnyHeightMap + Send + Sync)> {
-        self.subs
-            .as_vec()
+        self.as_vec()
             .into_iter()
             .flat_map(|d| d.to_any_height_map_vec())
             .collect_vec()
     }
 
     fn to_any_date_map_vec(&self) -> Vec<&(dyn AnyDateMap + Send + Sync)> {
-        self.subs
-            .as_vec()
+        self.as_vec()
             .into_iter()
             .flat_map(|d| d.to_any_date_map_vec())
             .collect_vec()
     }
 
     fn to_any_bi_map_vec(&self) -> Vec<&(dyn AnyBiMap + Send + Sync)> {
-        self.subs
-            .as_vec()
+        self.as_vec()
             .into_iter()
             .flat_map(|d| d.to_any_bi_map_vec())
             .collect_vec()
     }
 
     fn to_any_mut_height_map_vec(&mut self) -> Vec<&mut dyn AnyHeightMap> {
-        self.subs
-            .as_mut_vec()
+        self.as_mut_vec()
             .into_iter()
             .flat_map(|d| d.to_any_mut_height_map_vec())
             .collect_vec()
     }
 
     fn to_any_mut_date_map_vec(&mut self) -> Vec<&mut dyn AnyDateMap> {
-        self.subs
-            .as_mut_vec()
+        self.as_mut_vec()
             .into_iter()
             .flat_map(|d| d.to_any_mut_date_map_vec())
             .collect_vec()
     }
 
     fn to_any_mut_bi_map_vec(&mut self) -> Vec<&mut dyn AnyBiMap> {
-        self.subs
-            .as_mut_vec()
+        self.as_mut_vec()
             .into_iter()
             .flat_map(|d| d.to_any_mut_bi_map_vec())
             .collect_vec()
     }
+
+    fn to_dollar_bi_map_vec(&self) -> Vec<&(dyn AnyDollarBiMap + Send + Sync)> {
+        self.as_vec()
+            .into_iter()
+            .flat_map(|d| d.to_dollar_bi_map_vec())
+            .collect_vec()
+    }
 }
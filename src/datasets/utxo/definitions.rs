@@ -0,0 +1,83 @@
+use std::{fs, path::Path};
+
+use color_eyre::eyre::eyre;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{Config, UTXOCohortDefinition},
+    io::{format_path, Json},
+    states::UTXOCohortId,
+};
+
+use super::UTXODataset;
+
+///
+/// Definitions of the UTXO cohorts that depend on the config, saved at `{datasets_path}/utxo_cohorts.json`.
+///
+/// Datasets are only recomputed when they're missing, so a cohort whose definition changed but kept its name
+/// would silently keep its old values.
+///
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct UTXOCohortsDefinitions {
+    sth_days: u32,
+    customs: Vec<UTXOCohortDefinition>,
+}
+
+impl UTXOCohortsDefinitions {
+    /// Fails if the definition of a cohort that was already computed changed, saves the current ones otherwise
+    pub fn check(parent_path: &str) -> color_eyre::Result<()> {
+        let config = Config::get();
+
+        let path = format!("{parent_path}/utxo_cohorts.json");
+
+        let current = Self {
+            sth_days: config.sth_days,
+            customs: config.utxo_cohorts.clone(),
+        };
+
+        // Before this file existed, the datasets are assumed to match the config
+        if let Ok(stored) = Json::import::<Self>(&path) {
+            if stored == current {
+                return Ok(());
+            }
+
+            let mut changed = vec![];
+
+            if stored.sth_days != current.sth_days {
+                changed.push(UTXOCohortId::ShortTermHolders);
+                changed.push(UTXOCohortId::LongTermHolders);
+            }
+
+            changed.extend(
+                current
+                    .customs
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, definition)| {
+                        stored.customs.iter().any(|stored_definition| {
+                            stored_definition.name == definition.name
+                                && stored_definition != *definition
+                        })
+                    })
+                    .map(|(index, _)| UTXOCohortId::Custom(index)),
+            );
+
+            let folders = changed
+                .into_iter()
+                .map(|id| format_path(&UTXODataset::folder_path(parent_path, id)))
+                .filter(|folder_path| Path::new(folder_path).exists())
+                .collect::<Vec<_>>();
+
+            if !folders.is_empty() {
+                return Err(eyre!(
+                    "UTXO cohorts were computed with another definition, remove {} to compute them again",
+                    folders.join(", ")
+                ));
+            }
+        }
+
+        fs::create_dir_all(parent_path)?;
+
+        Json::export(&path, &current)
+    }
+}
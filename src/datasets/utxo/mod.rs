@@ -1,26 +1,49 @@
 mod dataset;
+mod definitions;
 
 use dataset::*;
+use definitions::*;
 
-use std::thread;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::Path,
+    thread::{self, ScopedJoinHandle},
+};
 
+use chrono::NaiveDate;
 use itertools::Itertools;
 
 use crate::{
+    bitcoin::{height_to_epoch, GENESIS_YEAR},
+    config::Config,
     datasets::AnyDatasets,
+    io::format_path,
+    parse::HEIGHT_MAP_CHUNK_SIZE,
     states::{SplitByUTXOCohort, UTXOCohortId},
+    utils::timestamp_to_year,
 };
 
-use super::{AnyDataset, MinInitialState, ProcessedBlockData};
+use super::{AnyDataset, MinInitialState, ProcessedBlockData, URPDSubDataset};
 
 pub struct UTXODatasets {
     min_initial_state: MinInitialState,
 
+    path: String,
+    /// Given to the cohorts that are added while parsing
+    pub first_provisional_height: Option<usize>,
+    pub first_provisional_date: Option<NaiveDate>,
+    /// Of the last block for which the missing cohorts were added, `None` until the first block after startup
+    year_and_epoch: Option<(u32, u32)>,
+
     cohorts: SplitByUTXOCohort<UTXODataset>,
+    urpd: URPDSubDataset,
 }
 
 impl UTXODatasets {
     pub fn import(parent_path: &str) -> color_eyre::Result<Self> {
+        UTXOCohortsDefinitions::check(parent_path)?;
+
         thread::scope(|scope| {
             let up_to_1d_handle =
                 scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::UpTo1d));
@@ -77,38 +100,28 @@ impl UTXODatasets {
             let from_10y_handle =
                 scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::From10y));
 
-            let year_2009_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2009));
-            let year_2010_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2010));
-            let year_2011_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2011));
-            let year_2012_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2012));
-            let year_2013_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2013));
-            let year_2014_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2014));
-            let year_2015_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2015));
-            let year_2016_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2016));
-            let year_2017_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2017));
-            let year_2018_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2018));
-            let year_2019_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2019));
-            let year_2020_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2020));
-            let year_2021_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2021));
-            let year_2022_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2022));
-            let year_2023_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2023));
-            let year_2024_handle =
-                scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::Year2024));
+            let years_handles = Self::existing_ids(parent_path, "year", UTXOCohortId::Year)
+                .into_iter()
+                .map(|id| scope.spawn(move || UTXODataset::import(parent_path, id)))
+                .collect_vec();
+
+            let epochs_handles = if Config::get().epoch_cohorts {
+                Self::existing_ids(parent_path, "epoch", UTXOCohortId::Epoch)
+            } else {
+                vec![]
+            }
+            .into_iter()
+            .map(|id| scope.spawn(move || UTXODataset::import(parent_path, id)))
+            .collect_vec();
+
+            // Computed from scratch when added, like any new dataset
+            let customs_handles = (0..Config::get().utxo_cohorts.len())
+                .map(|index| {
+                    scope.spawn(move || {
+                        UTXODataset::import(parent_path, UTXOCohortId::Custom(index))
+                    })
+                })
+                .collect_vec();
 
             let sth_handle =
                 scope.spawn(|| UTXODataset::import(parent_path, UTXOCohortId::ShortTermHolders));
@@ -118,6 +131,12 @@ impl UTXODatasets {
             let mut s = Self {
                 min_initial_state: MinInitialState::default(),
 
+                path: parent_path.to_owned(),
+                first_provisional_height: None,
+                first_provisional_date: None,
+                year_and_epoch: None,
+                urpd: URPDSubDataset::import(parent_path)?,
+
                 cohorts: SplitByUTXOCohort {
                     up_to_1d: up_to_1d_handle.join().unwrap()?,
                     up_to_1w: up_to_1w_handle.join().unwrap()?,
@@ -151,22 +170,13 @@ impl UTXODatasets {
                     sth: sth_handle.join().unwrap()?,
                     lth,
 
-                    year_2009: year_2009_handle.join().unwrap()?,
-                    year_2010: year_2010_handle.join().unwrap()?,
-                    year_2011: year_2011_handle.join().unwrap()?,
-                    year_2012: year_2012_handle.join().unwrap()?,
-                    year_2013: year_2013_handle.join().unwrap()?,
-                    year_2014: year_2014_handle.join().unwrap()?,
-                    year_2015: year_2015_handle.join().unwrap()?,
-                    year_2016: year_2016_handle.join().unwrap()?,
-                    year_2017: year_2017_handle.join().unwrap()?,
-                    year_2018: year_2018_handle.join().unwrap()?,
-                    year_2019: year_2019_handle.join().unwrap()?,
-                    year_2020: year_2020_handle.join().unwrap()?,
-                    year_2021: year_2021_handle.join().unwrap()?,
-                    year_2022: year_2022_handle.join().unwrap()?,
-                    year_2023: year_2023_handle.join().unwrap()?,
-                    year_2024: year_2024_handle.join().unwrap()?,
+                    years: Self::join_cohorts(years_handles)?,
+                    epochs: Self::join_cohorts(epochs_handles)?,
+                    customs: customs_handles
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, handle)| Ok((index, handle.join().unwrap()?)))
+                        .collect::<color_eyre::Result<_>>()?,
                 },
             };
 
@@ -177,19 +187,175 @@ impl UTXODatasets {
         })
     }
 
-    pub fn insert_data(&mut self, processed_block_data: &ProcessedBlockData) {
+    pub fn insert_data(
+        &mut self,
+        processed_block_data: &ProcessedBlockData,
+    ) -> color_eyre::Result<()> {
+        let &ProcessedBlockData {
+            height, timestamp, ..
+        } = processed_block_data;
+
+        let year_and_epoch = (timestamp_to_year(timestamp), height_to_epoch(height));
+
+        if self.year_and_epoch != Some(year_and_epoch) {
+            self.add_missing_cohorts(height, year_and_epoch)?;
+
+            self.year_and_epoch = Some(year_and_epoch);
+        }
+
         self.cohorts
             .as_mut_vec()
             .into_iter()
-            .for_each(|cohort| cohort.insert_data(processed_block_data))
+            .try_for_each(|cohort| cohort.insert_data(processed_block_data))?;
+
+        self.insert_urpd(processed_block_data);
+
+        Ok(())
     }
 
-    fn as_vec(&self) -> Vec<&UTXODataset> {
-        self.cohorts.as_vec()
+    /// The one of the whole network, made of the short and long term holders since they don't overlap
+    fn insert_urpd(&mut self, processed_block_data: &ProcessedBlockData) {
+        let one_shot_states = processed_block_data.utxo_cohorts_one_shot_states;
+
+        let (Some(sth), Some(lth)) = (
+            one_shot_states.sth.urpd.as_ref(),
+            one_shot_states.lth.urpd.as_ref(),
+        ) else {
+            return;
+        };
+
+        let mut urpd = sth.clone();
+        urpd.merge(lth);
+
+        self.urpd.insert(processed_block_data.date, urpd)
     }
 
-    fn as_mut_vec(&mut self) -> Vec<&mut UTXODataset> {
-        self.cohorts.as_mut_vec()
+    ///
+    /// Every year since the genesis one up to the next one, and every epoch up to the next one if enabled.
+    ///
+    /// The next ones are added early since they're all zeros until they start.
+    /// A cohort added late, like a year that was skipped, only has zeros before the height it was added at.
+    ///
+    fn add_missing_cohorts(
+        &mut self,
+        height: usize,
+        (year, epoch): (u32, u32),
+    ) -> color_eyre::Result<()> {
+        let mut ids = (GENESIS_YEAR..=year + 1)
+            .filter(|year| !self.cohorts.years.contains_key(year))
+            .map(UTXOCohortId::Year)
+            .collect_vec();
+
+        if Config::get().epoch_cohorts {
+            ids.extend(
+                (0..=epoch + 1)
+                    .filter(|epoch| !self.cohorts.epochs.contains_key(epoch))
+                    .map(UTXOCohortId::Epoch),
+            );
+        }
+
+        // From the chunk of the previous height, so that the maps of a cohort don't have a gap at the beginning
+        // of their first chunk and that cumulative ones have a previous value
+        let start = height.checked_sub(1).map_or(0, |previous| {
+            previous / HEIGHT_MAP_CHUNK_SIZE * HEIGHT_MAP_CHUNK_SIZE
+        });
+
+        ids.into_iter()
+            .try_for_each(|id| -> color_eyre::Result<()> {
+                println!("Adding the UTXO cohort {}...", id.name());
+
+                let mut dataset = UTXODataset::import(&self.path, id)?;
+
+                dataset.fill_default_heights(start..height);
+
+                dataset.set_first_provisional_height(
+                    self.first_provisional_height,
+                    self.first_provisional_date,
+                );
+
+                match id {
+                    UTXOCohortId::Year(year) => self.cohorts.years.insert(year, dataset),
+                    UTXOCohortId::Epoch(epoch) => self.cohorts.epochs.insert(epoch, dataset),
+                    _ => unreachable!(),
+                };
+
+                Ok(())
+            })
+    }
+
+    /// Removes the year and epoch cohorts left without any value, which happens when rolling back to before they were added
+    pub fn remove_empty_cohorts(&mut self) -> color_eyre::Result<()> {
+        self.year_and_epoch = None;
+
+        let mut removed = vec![];
+
+        self.cohorts.years.retain(|_, dataset| {
+            dataset.has_values() || {
+                removed.push(dataset.id());
+                false
+            }
+        });
+
+        self.cohorts.epochs.retain(|_, dataset| {
+            dataset.has_values() || {
+                removed.push(dataset.id());
+                false
+            }
+        });
+
+        removed
+            .into_iter()
+            .try_for_each(|id| -> color_eyre::Result<()> {
+                let folder_path = format_path(&UTXODataset::folder_path(&self.path, id));
+
+                if Path::new(&folder_path).exists() {
+                    fs::remove_dir_all(folder_path)?;
+                }
+
+                Ok(())
+            })
+    }
+
+    /// Cohorts of a kind that were added in a previous run
+    fn existing_ids(
+        parent_path: &str,
+        kind: &str,
+        to_id: fn(u32) -> UTXOCohortId,
+    ) -> Vec<UTXOCohortId> {
+        let Ok(entries) = fs::read_dir(format_path(&format!("{parent_path}/{kind}"))) else {
+            return vec![];
+        };
+
+        entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+            .sorted()
+            .map(to_id)
+            .collect_vec()
+    }
+
+    /// Ones without values are left to be added again while parsing, instead of making everything be computed again
+    fn join_cohorts(
+        handles: Vec<ScopedJoinHandle<color_eyre::Result<UTXODataset>>>,
+    ) -> color_eyre::Result<BTreeMap<u32, UTXODataset>> {
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .filter_ok(|dataset| dataset.has_values())
+            .map_ok(|dataset| {
+                let key = match dataset.id() {
+                    UTXOCohortId::Year(year) => year,
+                    UTXOCohortId::Epoch(epoch) => epoch,
+                    _ => unreachable!(),
+                };
+
+                (key, dataset)
+            })
+            .collect()
+    }
+
+    fn as_vec(&self) -> Vec<&UTXODataset> {
+        self.cohorts.as_vec()
     }
 }
 
@@ -202,13 +368,20 @@ impl AnyDatasets for UTXODatasets {
         self.as_vec()
             .into_iter()
             .map(|dataset| dataset as &(dyn AnyDataset + Send + Sync))
+            .chain([&self.urpd as &(dyn AnyDataset + Send + Sync)])
             .collect_vec()
     }
 
     fn to_mut_any_dataset_vec(&mut self) -> Vec<&mut dyn AnyDataset> {
-        self.as_mut_vec()
+        let mut vec = self
+            .cohorts
+            .as_mut_vec()
             .into_iter()
             .map(|dataset| dataset as &mut dyn AnyDataset)
-            .collect_vec()
+            .collect_vec();
+
+        vec.push(&mut self.urpd);
+
+        vec
     }
 }
@@ -1,4 +1,14 @@
-use savefile::{load_file, save_file, Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use savefile::{load, save, Deserialize, Serialize};
+
+use crate::config::Config;
+
+use super::tmp_path;
 
 pub struct Binary;
 
@@ -7,18 +17,214 @@ pub struct Binary;
 // savefile: less consistent maybe even slower but good enough for now (as of height ~350 000)
 // rkyv: need to try but having an archived mirror of all serialized struct seems annoying
 
+pub const BINARY_EXTENSION: &str = "bin";
+/// Added right after the binary extension of the files written compressed, `x.bin` becoming `x.bin.zst`
+pub const COMPRESSED_EXTENSION: &str = "zst";
+
 impl Binary {
+    /// Reads the compressed file if there's one, the uncompressed one otherwise
     pub fn import<T>(path: &str) -> color_eyre::Result<T>
     where
         T: Deserialize,
     {
-        Ok(load_file(path, 0)?)
+        let path = Self::existing_path(path).unwrap_or_else(|| path.to_owned());
+
+        let mut reader = BufReader::new(File::open(&path)?);
+
+        if Self::is_compressed(&path) {
+            Ok(load(&mut zstd::Decoder::with_buffer(reader)?, 0)?)
+        } else {
+            Ok(load(&mut reader, 0)?)
+        }
     }
 
+    /// Compressed if a zstd level is set
     pub fn export<T>(path: &str, value: &T) -> color_eyre::Result<()>
     where
         T: Serialize,
     {
-        Ok(save_file(path, 0, value)?)
+        Self::export_with_level(path, value, Config::get().zstd_level)
+    }
+
+    fn export_with_level<T>(path: &str, value: &T, level: Option<i32>) -> color_eyre::Result<()>
+    where
+        T: Serialize,
+    {
+        let path = Self::uncompressed_path(path);
+
+        let compressed = level.zip(Self::compressed_path(&path));
+
+        let path = compressed
+            .as_ref()
+            .map_or(path.clone(), |(_, compressed)| compressed.clone());
+
+        let tmp_path = tmp_path(&path);
+
+        let file = File::create(&tmp_path)?;
+
+        let file = match compressed {
+            Some((level, _)) => {
+                let mut encoder = zstd::Encoder::new(file, level)?;
+
+                save(&mut encoder, 0, value)?;
+
+                encoder.finish()?
+            }
+            None => {
+                let mut writer = BufWriter::new(file);
+
+                save(&mut writer, 0, value)?;
+
+                writer.into_inner()?
+            }
+        };
+
+        file.sync_all()?;
+
+        fs::rename(tmp_path, &path)?;
+
+        Ok(Self::remove_counterpart(&path)?)
+    }
+
+    /// Whether the file exists, compressed or not
+    pub fn exists(path: &str) -> bool {
+        Self::existing_path(path).is_some()
+    }
+
+    /// Path of the file as it was written, compressed or not, `None` if there's neither
+    pub fn existing_path(path: &str) -> Option<String> {
+        let path = Self::uncompressed_path(path);
+
+        Self::compressed_path(&path)
+            .into_iter()
+            .chain([path])
+            .find(|path| Path::new(path).exists())
+    }
+
+    /// Like `fs::remove_file` but for both the compressed and the uncompressed file
+    pub fn remove(path: &str) -> io::Result<()> {
+        let path = Self::uncompressed_path(path);
+
+        let result = fs::remove_file(&path);
+
+        match Self::compressed_path(&path) {
+            Some(compressed) if Path::new(&compressed).exists() => fs::remove_file(compressed),
+            _ => result,
+        }
+    }
+
+    /// Removes the uncompressed file if `path` is the compressed one and vice versa,
+    /// so that switching the zstd level never leaves an outdated file behind
+    pub fn remove_counterpart(path: &str) -> io::Result<()> {
+        let counterpart = if Self::is_compressed(path) {
+            Some(Self::uncompressed_path(path))
+        } else {
+            Self::compressed_path(path)
+        };
+
+        match counterpart {
+            Some(counterpart) if Path::new(&counterpart).exists() => fs::remove_file(counterpart),
+            _ => Ok(()),
+        }
+    }
+
+    /// Extensions that follow the binary one (like staged ones) are kept: `x.bin.5.staged` becomes `x.bin.zst.5.staged`.
+    /// `None` for files without a binary extension, like database journals, which are never compressed
+    pub fn compressed_path(path: &str) -> Option<String> {
+        let path = Self::uncompressed_path(path);
+
+        let (start, rest) = split_binary_extension(&path)?;
+
+        Some(format!("{start}.{COMPRESSED_EXTENSION}{rest}"))
+    }
+
+    pub fn is_compressed(path: &str) -> bool {
+        Self::uncompressed_path(path) != path
+    }
+
+    pub fn uncompressed_path(path: &str) -> String {
+        match split_binary_extension(path) {
+            Some((start, rest)) => match rest.strip_prefix(&format!(".{COMPRESSED_EXTENSION}")) {
+                Some(rest) if rest.is_empty() || rest.starts_with('.') => format!("{start}{rest}"),
+                _ => path.to_owned(),
+            },
+            None => path.to_owned(),
+        }
+    }
+}
+
+/// Splits the file name right after its binary extension
+fn split_binary_extension(path: &str) -> Option<(&str, &str)> {
+    let file_name_start = path.rfind('/').map_or(0, |index| index + 1);
+
+    let extension = format!(".{BINARY_EXTENSION}");
+
+    path[file_name_start..]
+        .match_indices(&extension)
+        .map(|(index, _)| file_name_start + index + extension.len())
+        .find(|&end| path[end..].is_empty() || path[end..].starts_with('.'))
+        .map(|end| path.split_at(end))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_compressed_paths() {
+        assert_eq!(
+            Binary::compressed_path("a/x.bin").as_deref(),
+            Some("a/x.bin.zst")
+        );
+        assert_eq!(
+            Binary::compressed_path("a/x.bin.5.staged").as_deref(),
+            Some("a/x.bin.zst.5.staged")
+        );
+        assert_eq!(Binary::compressed_path("a.bin/db.5.journal"), None);
+        assert_eq!(
+            Binary::uncompressed_path("a/x.bin.zst.5.staged"),
+            "a/x.bin.5.staged"
+        );
+        assert_eq!(Binary::uncompressed_path("a/x.bin"), "a/x.bin");
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+
+        let folder = env::temp_dir().join(format!("binary{nanos}"));
+
+        fs::create_dir_all(&folder).unwrap();
+
+        let path = folder.join("values.bin");
+        let path = path.to_str().unwrap();
+        let compressed = Binary::compressed_path(path).unwrap();
+
+        let values: Vec<u64> = (0..1_000).collect();
+
+        Binary::export_with_level(path, &values, Some(3)).unwrap();
+
+        assert!(Path::new(&compressed).exists());
+        assert!(!Path::new(path).exists());
+        assert_eq!(Binary::import::<Vec<u64>>(path).unwrap(), values);
+
+        Binary::export_with_level(path, &values, None).unwrap();
+
+        assert!(!Path::new(&compressed).exists());
+        assert_eq!(Binary::import::<Vec<u64>>(&compressed).unwrap(), values);
+
+        Binary::remove(path).unwrap();
+
+        assert!(!Binary::exists(path));
+
+        fs::remove_dir_all(folder).unwrap();
     }
 }
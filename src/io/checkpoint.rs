@@ -0,0 +1,123 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+use super::{split_height_extension, Binary, Json, JOURNAL_EXTENSION, STAGED_EXTENSION};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Commit {
+    pub height: usize,
+    pub date: NaiveDate,
+}
+
+///
+/// Manifest of the last height at which each component was saved.
+///
+/// States and databases are first staged next to their files and only become the current version once
+/// the manifest, written atomically, says so. An interrupted save thus always recovers to the last
+/// complete checkpoint instead of forcing everything to be parsed again.
+///
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+#[serde(default)]
+pub struct Checkpoint {
+    pub datasets: Option<Commit>,
+    pub databases: Option<Commit>,
+    pub states: Option<Commit>,
+}
+
+impl Checkpoint {
+    fn path() -> String {
+        format!("{}/checkpoint.json", Config::get().outputs_path)
+    }
+
+    pub fn import() -> Self {
+        Json::import(&Self::path()).unwrap_or_default()
+    }
+
+    pub fn export(&self) -> color_eyre::Result<()> {
+        fs::create_dir_all(&Config::get().outputs_path)?;
+
+        Json::export(&Self::path(), self)
+    }
+
+    /// Renames the staged files of committed checkpoints and removes the leftovers of interrupted ones
+    pub fn recover(&self) -> color_eyre::Result<()> {
+        let outputs_path = &Config::get().outputs_path;
+
+        recover_folder(
+            Path::new(&format!("{outputs_path}/states")),
+            self.states.map(|commit| commit.height),
+        )?;
+
+        recover_folder(
+            Path::new(&format!("{outputs_path}/databases")),
+            self.databases.map(|commit| commit.height),
+        )
+    }
+}
+
+fn recover_folder(folder: &Path, committed_height: Option<usize>) -> color_eyre::Result<()> {
+    let is_committed =
+        |height: usize| committed_height.is_some_and(|committed| height <= committed);
+
+    let mut staged = vec![];
+
+    for path in list_files(folder)? {
+        let path_str = path.to_str().unwrap();
+
+        if path_str.ends_with(".tmp") {
+            fs::remove_file(&path)?;
+        } else if let Some((original, height)) = split_height_extension(path_str, STAGED_EXTENSION)
+        {
+            if is_committed(height) {
+                staged.push((height, original.to_owned(), path.clone()));
+            } else {
+                fs::remove_file(&path)?;
+            }
+        } else if let Some((_, height)) = split_height_extension(path_str, JOURNAL_EXTENSION) {
+            // Committed journals are applied when their database is opened
+            if !is_committed(height) {
+                fs::remove_file(&path)?;
+            }
+        }
+    }
+
+    // Oldest first so that the most recent version wins
+    staged.sort();
+
+    staged
+        .into_iter()
+        .try_for_each(|(_, original, path)| -> color_eyre::Result<()> {
+            fs::rename(path, &original)?;
+
+            Ok(Binary::remove_counterpart(&original)?)
+        })?;
+
+    Ok(())
+}
+
+pub fn list_files(folder: &Path) -> color_eyre::Result<Vec<PathBuf>> {
+    if !folder.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            files.append(&mut list_files(&path)?);
+        } else {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
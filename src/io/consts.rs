@@ -1,2 +0,0 @@
-pub const IMPORTS_FOLDER_PATH: &str = "./imports";
-pub const OUTPUTS_FOLDER_PATH: &str = "./target/outputs";
@@ -0,0 +1,153 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufRead, BufReader, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+///
+/// Tables whose rows are sorted by key and only ever appended to.
+///
+/// A row is only complete with its line break, anything after the last one is the leftover
+/// of an interrupted append and is ignored until truncated.
+///
+pub struct Csv;
+
+impl Csv {
+    pub fn read_header(path: &str) -> color_eyre::Result<Option<String>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+
+        let mut header = String::new();
+
+        BufReader::new(File::open(path)?).read_line(&mut header)?;
+
+        Ok(header.strip_suffix('\n').map(|header| header.to_owned()))
+    }
+
+    /// Last complete row, without reading the whole file
+    pub fn read_last_row(path: &str) -> color_eyre::Result<Option<String>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+
+        let mut file = File::open(path)?;
+
+        let len = file.metadata()?.len();
+
+        let mut tail_len = 1024.min(len);
+
+        loop {
+            file.seek(SeekFrom::Start(len - tail_len))?;
+
+            let mut tail = vec![];
+            Read::by_ref(&mut file)
+                .take(tail_len)
+                .read_to_end(&mut tail)?;
+
+            let tail = String::from_utf8_lossy(&tail);
+
+            let Some((complete, _)) = tail.rsplit_once('\n') else {
+                if tail_len == len {
+                    return Ok(None);
+                }
+
+                tail_len = (tail_len * 2).min(len);
+                continue;
+            };
+
+            match complete.rsplit_once('\n') {
+                Some((_, last)) => return Ok(Some(last.to_owned())),
+                // The only complete row is the header
+                None if tail_len == len => return Ok(None),
+                None => tail_len = (tail_len * 2).min(len),
+            }
+        }
+    }
+
+    /// Keeps the header and the first rows for which `keep` is true, removing everything after
+    pub fn truncate(path: &str, keep: impl Fn(&str) -> bool) -> color_eyre::Result<()> {
+        if !Path::new(path).exists() {
+            return Ok(());
+        }
+
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let mut kept_len = 0;
+        let mut line = String::new();
+        let mut is_header = true;
+
+        loop {
+            line.clear();
+
+            let read = reader.read_line(&mut line)?;
+
+            let Some(row) = line.strip_suffix('\n') else {
+                break;
+            };
+
+            if !is_header && !keep(row) {
+                break;
+            }
+
+            is_header = false;
+            kept_len += read as u64;
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .set_len(kept_len)?;
+
+        Ok(())
+    }
+
+    pub fn append(path: &str, header: &str, rows: &[String]) -> color_eyre::Result<()> {
+        if !Self::ends_with_line_break(path)? {
+            Self::truncate(path, |_| true)?;
+        }
+
+        let is_new = !fs::metadata(path).is_ok_and(|metadata| metadata.len() > 0);
+
+        if let Some(parent) = Path::new(path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        let mut text = String::new();
+
+        if is_new {
+            text += header;
+            text.push('\n');
+        }
+
+        rows.iter().for_each(|row| {
+            text += row;
+            text.push('\n');
+        });
+
+        file.write_all(text.as_bytes())?;
+
+        file.sync_all()?;
+
+        Ok(())
+    }
+
+    fn ends_with_line_break(path: &str) -> color_eyre::Result<bool> {
+        let Ok(mut file) = File::open(path) else {
+            return Ok(true);
+        };
+
+        if file.metadata()?.len() == 0 {
+            return Ok(true);
+        }
+
+        file.seek(SeekFrom::End(-1))?;
+
+        let mut last = [0; 1];
+        file.read_exact(&mut last)?;
+
+        Ok(last[0] == b'\n')
+    }
+}
@@ -1,10 +1,12 @@
 use std::{
-    fs::File,
+    fs::{self, File},
     io::{BufReader, BufWriter},
 };
 
 use serde::{de::DeserializeOwned, Serialize};
 
+use super::tmp_path;
+
 pub struct Json;
 
 impl Json {
@@ -23,7 +25,9 @@ impl Json {
     where
         T: Serialize,
     {
-        let file = File::create(path).unwrap_or_else(|_| {
+        let tmp_path = tmp_path(path);
+
+        let file = File::create(&tmp_path).unwrap_or_else(|_| {
             dbg!(&path);
             panic!("No such file or directory")
         });
@@ -32,6 +36,8 @@ impl Json {
 
         serde_json::to_writer_pretty(&mut writer, value)?;
 
-        Ok(())
+        writer.into_inner()?.sync_all()?;
+
+        Ok(fs::rename(tmp_path, path)?)
     }
 }
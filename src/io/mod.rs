@@ -1,11 +1,15 @@
 mod binary;
-mod consts;
+mod checkpoint;
+mod csv;
 mod json;
 mod path;
 mod serialization;
+mod snapshot;
 
 pub use binary::*;
-pub use consts::*;
+pub use checkpoint::*;
+pub use csv::*;
 pub use json::*;
 pub use path::*;
 pub use serialization::*;
+pub use snapshot::*;
@@ -1,3 +1,50 @@
+use crate::config::Config;
+
 pub fn format_path(path: &str) -> String {
     path.replace(['-', '_', ' '], "/")
 }
+
+/// Path of a map relative to the datasets folder, or to the price folder prefixed by `price/`
+pub fn relative_map_path(path: &str) -> Option<String> {
+    let config = Config::get();
+
+    [
+        (format_path(&config.datasets_path), ""),
+        (format_path(&config.price_path), "price/"),
+    ]
+    .into_iter()
+    .find_map(|(root, prefix)| {
+        let relative = path.strip_prefix(root.as_str())?.strip_prefix('/')?;
+
+        Some(format!("{prefix}{relative}"))
+    })
+}
+
+/// Files are written next to their destination first and then renamed, which is atomic,
+/// so that an interrupted write never leaves a truncated file behind
+pub fn tmp_path(path: &str) -> String {
+    format!("{path}.tmp")
+}
+
+pub const STAGED_EXTENSION: &str = "staged";
+pub const JOURNAL_EXTENSION: &str = "journal";
+
+/// Written before a checkpoint and only renamed to `path` once the checkpoint is committed
+pub fn staged_path(path: &str, height: usize) -> String {
+    format!("{path}.{height}.{STAGED_EXTENSION}")
+}
+
+/// Changes of a database to be applied once the checkpoint at `height` is committed
+pub fn journal_path(path: &str, height: usize) -> String {
+    format!("{path}.{height}.{JOURNAL_EXTENSION}")
+}
+
+/// Returns the original path and the height of a staged or journal path
+pub fn split_height_extension<'a>(path: &'a str, extension: &str) -> Option<(&'a str, usize)> {
+    let (path, height) = path
+        .strip_suffix(extension)?
+        .strip_suffix('.')?
+        .rsplit_once('.')?;
+
+    Some((path, height.parse().ok()?))
+}
@@ -1,8 +1,8 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, fs, io, path::Path};
 
 use serde::{de::DeserializeOwned, Serialize};
 
-use crate::io::{Binary, Json};
+use crate::io::{Binary, Json, BINARY_EXTENSION, COMPRESSED_EXTENSION};
 
 #[derive(PartialEq, PartialOrd, Ord, Eq)]
 pub enum Serialization {
@@ -13,23 +13,75 @@ pub enum Serialization {
 impl Serialization {
     pub fn to_extension(&self) -> &str {
         match self {
-            Self::Binary => "bin",
+            Self::Binary => BINARY_EXTENSION,
             Self::Json => "json",
         }
     }
 
     pub fn from_extension(extension: &str) -> Self {
         match extension {
-            "bin" => Self::Binary,
+            BINARY_EXTENSION => Self::Binary,
             "json" => Self::Json,
             _ => panic!("Extension \"{extension}\" isn't supported"),
         }
     }
 
+    /// Chunks of a map all have the same extension, `None` if there are none yet
+    pub fn find(folder: &str) -> color_eyre::Result<Option<Self>> {
+        if !Path::new(folder).exists() {
+            return Ok(None);
+        }
+
+        for entry in fs::read_dir(folder)? {
+            let file_name = entry?.file_name();
+
+            if let Some((_, serialization)) = file_name.to_str().and_then(Self::split_file_name) {
+                return Ok(Some(serialization));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Name without its extension(s) and serialization of a file that was exported by one,
+    /// `None` for the others (.tmp, .staged, .csv...)
+    pub fn split_file_name(file_name: &str) -> Option<(&str, Self)> {
+        let (name, extension) = file_name.rsplit_once('.')?;
+
+        match extension {
+            BINARY_EXTENSION => Some((name, Self::Binary)),
+            COMPRESSED_EXTENSION => Some((
+                name.strip_suffix(BINARY_EXTENSION)?.strip_suffix('.')?,
+                Self::Binary,
+            )),
+            "json" => Some((name, Self::Json)),
+            _ => None,
+        }
+    }
+
     pub fn append_extension(&self, path: &str) -> String {
         format!("{path}.{}", self.to_extension())
     }
 
+    pub fn exists(&self, path: &str) -> bool {
+        self.existing_path(path).is_some()
+    }
+
+    /// Path of the file as it was written, which for binary files depends on whether they were compressed
+    pub fn existing_path(&self, path: &str) -> Option<String> {
+        match self {
+            Serialization::Binary => Binary::existing_path(path),
+            Serialization::Json => Some(path.to_owned()).filter(|path| Path::new(path).exists()),
+        }
+    }
+
+    pub fn remove(&self, path: &str) -> io::Result<()> {
+        match self {
+            Serialization::Binary => Binary::remove(path),
+            Serialization::Json => fs::remove_file(path),
+        }
+    }
+
     pub fn import<T>(&self, path: &str) -> color_eyre::Result<T>
     where
         T: savefile::Deserialize + DeserializeOwned + Debug,
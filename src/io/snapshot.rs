@@ -0,0 +1,191 @@
+use std::{fs, io, path::Path};
+
+use crate::{config::Config, datasets::MinInitialState, utils::time};
+
+use super::{tmp_path, Checkpoint, Commit, Json};
+
+///
+/// Copy of the states and databases as they were committed at a given height.
+///
+/// Parsing can resume from one instead of genesis when some datasets are behind the states,
+/// in which case only those are recomputed while the others are skipped by `should_insert`.
+///
+/// Databases are copied whole and not just their metadata since fully spent transactions
+/// are removed from them and would be missing when parsing again.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct Snapshot {
+    pub commit: Commit,
+}
+
+impl Snapshot {
+    pub fn folder_path() -> String {
+        format!("{}/snapshots", Config::get().outputs_path)
+    }
+
+    fn path(&self) -> String {
+        format!("{}/{}", Self::folder_path(), self.commit.height)
+    }
+
+    /// Sorted by height
+    pub fn list() -> Vec<Self> {
+        let Ok(entries) = fs::read_dir(Self::folder_path()) else {
+            return vec![];
+        };
+
+        let mut snapshots = entries
+            .flat_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter_map(|path| {
+                // Skips the ones that were interrupted while being taken
+                path.file_name()?.to_str()?.parse::<usize>().ok()?;
+
+                let checkpoint: Checkpoint =
+                    Json::import(path.join("checkpoint.json").to_str()?).ok()?;
+
+                // Both are needed and committed together
+                let commit = checkpoint
+                    .states
+                    .filter(|states| Some(*states) == checkpoint.databases)?;
+
+                Some(Self { commit })
+            })
+            .collect::<Vec<_>>();
+
+        snapshots.sort_by_key(|snapshot| snapshot.commit.height);
+
+        snapshots
+    }
+
+    /// Most recent snapshot that isn't ahead of any dataset
+    pub fn find_replayable(datasets_min_initial_state: &MinInitialState) -> Option<Self> {
+        let MinInitialState {
+            last_date,
+            last_height,
+            ..
+        } = datasets_min_initial_state;
+
+        let (last_date, last_height) = (last_date.as_ref()?, last_height.as_ref()?);
+
+        Self::list().into_iter().rev().find(|snapshot| {
+            snapshot.commit.date <= *last_date && snapshot.commit.height <= *last_height
+        })
+    }
+
+    /// Takes a snapshot of what was just committed if the last one is from a previous period
+    pub fn take_if_due(commit: Commit) -> color_eyre::Result<()> {
+        let config = Config::get();
+
+        let Some(cadence) = config.states_snapshots else {
+            return Ok(());
+        };
+
+        let is_due = Self::list()
+            .last()
+            .is_none_or(|last| cadence.is_new_period(&last.commit, &commit));
+
+        if !is_due {
+            return Ok(());
+        }
+
+        time("States snapshot taken", || Self::take(commit))?;
+
+        Self::prune(config.states_snapshots_kept)
+    }
+
+    fn take(commit: Commit) -> color_eyre::Result<Self> {
+        let outputs_path = &Config::get().outputs_path;
+
+        let snapshot = Self { commit };
+
+        let path = snapshot.path();
+        let tmp_path = tmp_path(&path);
+
+        if Path::new(&tmp_path).exists() {
+            fs::remove_dir_all(&tmp_path)?;
+        }
+
+        ["states", "databases"].into_iter().try_for_each(|folder| {
+            copy_dir_all(
+                Path::new(&format!("{outputs_path}/{folder}")),
+                Path::new(&format!("{tmp_path}/{folder}")),
+            )
+        })?;
+
+        Json::export(
+            &format!("{tmp_path}/checkpoint.json"),
+            &Checkpoint {
+                datasets: None,
+                databases: Some(commit),
+                states: Some(commit),
+            },
+        )?;
+
+        if Path::new(&path).exists() {
+            fs::remove_dir_all(&path)?;
+        }
+
+        fs::rename(tmp_path, path)?;
+
+        Ok(snapshot)
+    }
+
+    /// Removes the oldest snapshots
+    fn prune(kept: usize) -> color_eyre::Result<()> {
+        let snapshots = Self::list();
+
+        let removed = snapshots.len().saturating_sub(kept);
+
+        snapshots
+            .iter()
+            .take(removed)
+            .try_for_each(|snapshot| fs::remove_dir_all(snapshot.path()))?;
+
+        Ok(())
+    }
+
+    /// Replaces the current states and databases by the snapshot's, which is kept as is
+    pub fn restore(&self) -> color_eyre::Result<()> {
+        let outputs_path = &Config::get().outputs_path;
+
+        let path = self.path();
+
+        ["states", "databases"]
+            .into_iter()
+            .try_for_each(|folder| -> color_eyre::Result<()> {
+                let dst = format!("{outputs_path}/{folder}");
+
+                if Path::new(&dst).exists() {
+                    fs::remove_dir_all(&dst)?;
+                }
+
+                copy_dir_all(Path::new(&format!("{path}/{folder}")), Path::new(&dst))?;
+
+                Ok(())
+            })?;
+
+        let mut checkpoint = Checkpoint::import();
+
+        checkpoint.databases.replace(self.commit);
+        checkpoint.states.replace(self.commit);
+
+        checkpoint.export()
+    }
+}
+
+pub fn copy_dir_all(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+
+    for entry in fs::read_dir(src)? {
+        let path = entry?.path();
+
+        let dst_path = dst.join(path.file_name().unwrap());
+
+        if path.is_dir() {
+            copy_dir_all(&path, &dst_path)?;
+        } else {
+            fs::copy(&path, dst_path)?;
+        }
+    }
+
+    Ok(())
+}
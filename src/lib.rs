@@ -1,17 +1,22 @@
 mod actions;
 mod bitcoin;
+mod config;
 mod databases;
 mod datasets;
 mod io;
 mod parse;
 mod price;
+mod server;
 mod states;
 mod utils;
 
 pub use crate::{
-    actions::iter_blocks,
-    bitcoin::{BitcoinDB, BitcoinDaemon},
+    actions::{import_candles, inspect, iter_blocks, reset, verify, ResetTargets},
+    bitcoin::{BitcoinDB, BitcoinDaemon, BlockSource, RpcAuth, RpcBlockSource},
+    config::{Args, Cli, Command, Config, ExportCadence},
     io::{Binary, Json, Serialization},
     parse::{DateMap, HeightMap, SerializedDateMap, SerializedHeightMap, HEIGHT_MAP_CHUNK_SIZE},
+    price::MissingPrice,
+    server::serve,
     utils::timestamp_to_naive_date,
 };
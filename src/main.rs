@@ -1,39 +1,116 @@
-use std::path::Path;
+use std::{path::Path, thread, time::Duration};
 
-use parser::{iter_blocks, BitcoinDB, BitcoinDaemon};
+use clap::Parser;
+use color_eyre::eyre::eyre;
+use parser::{
+    import_candles, inspect, iter_blocks, reset, verify, BitcoinDB, BitcoinDaemon, BlockSource,
+    Cli, Command, Config, MissingPrice, ResetTargets, RpcBlockSource,
+};
 
-const BITCOIN_DATADIR_RAW_PATH: &str = "/Users/k/Developer/bitcoin";
+const MISSING_PRICE_RETRY_DELAY: Duration = Duration::from_secs(10 * 60);
+const NEW_BLOCK_POLL_DELAY: Duration = Duration::from_secs(5);
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
 
-    let deamon = BitcoinDaemon::new(BITCOIN_DATADIR_RAW_PATH);
+    let Cli { args, command } = Cli::parse();
+
+    let config = Config::from_args(args)?.init();
+
+    match command.unwrap_or(Command::Watch) {
+        Command::Parse => {
+            parse(config)?;
+        }
+        Command::Watch => watch(config)?,
+        Command::Reset {
+            states,
+            databases,
+            datasets,
+        } => reset(ResetTargets {
+            databases,
+            datasets,
+            states,
+        })?,
+        Command::Inspect { path, height, date } => inspect(&path, height, date)?,
+        Command::Verify => {
+            if !verify()? {
+                return Err(eyre!("Verification failed"));
+            }
+        }
+        Command::ImportCandles { paths } => import_candles(&paths)?,
+    }
+
+    Ok(())
+}
+
+/// Returns the block count at the time of parsing
+fn parse(config: &Config) -> color_eyre::Result<usize> {
+    let block_source: Box<dyn BlockSource> = if let Some(rpc_url) = config.rpc_url.as_ref() {
+        Box::new(RpcBlockSource::new(rpc_url, config.rpc_auth()?)?)
+    } else if config.snapshot {
+        Box::new(BitcoinDB::new_from_snapshot(
+            Path::new(&config.bitcoin_datadir),
+            Path::new(&config.snapshot_path()),
+            true,
+        )?)
+    } else {
+        Box::new(BitcoinDB::new(Path::new(&config.bitcoin_datadir), true)?)
+    };
+
+    let block_count = block_source.get_block_count()?;
+    println!("{block_count} blocks found.");
+
+    iter_blocks(block_source.as_ref(), block_count)?;
+
+    Ok(block_count)
+}
+
+fn watch(config: &Config) -> color_eyre::Result<()> {
+    let deamon = BitcoinDaemon::new(&config.bitcoin_datadir);
 
     loop {
-        deamon.stop();
+        if config.needs_node_stopped() {
+            deamon.stop();
+        }
 
         // Scoped to free bitcoin's lock
-        let block_count = {
-            let bitcoin_db = BitcoinDB::new(Path::new(BITCOIN_DATADIR_RAW_PATH), true)?;
+        let block_count = match parse(config) {
+            Ok(block_count) => block_count,
+            // Everything up to the last export is kept, the rest is parsed again once the price is found
+            Err(error) if error.downcast_ref::<MissingPrice>().is_some() => {
+                if config.needs_node_stopped() {
+                    deamon.start();
+                }
 
-            let block_count = bitcoin_db.get_block_count();
-            println!("{block_count} blocks found.");
+                println!(
+                    "{error}, retrying in {} minutes...",
+                    MISSING_PRICE_RETRY_DELAY.as_secs() / 60
+                );
 
-            iter_blocks(&bitcoin_db, block_count)?;
+                thread::sleep(MISSING_PRICE_RETRY_DELAY);
 
-            block_count
+                continue;
+            }
+            Err(error) => return Err(error),
         };
 
-        deamon.start();
+        if config.needs_node_stopped() {
+            deamon.start();
+        }
+
+        // The node isn't local, only its RPC is reachable
+        if let Some(rpc_url) = config.rpc_url.as_ref() {
+            let block_source = RpcBlockSource::new(rpc_url, config.rpc_auth()?)?;
 
-        if deamon.check_if_fully_synced()? {
+            while block_source.get_block_count()? <= block_count {
+                thread::sleep(NEW_BLOCK_POLL_DELAY);
+            }
+        } else if deamon.check_if_fully_synced()? {
             deamon.wait_for_new_block(block_count - 1)?;
         } else {
             deamon.wait_sync()?;
         }
     }
-
-    // Ok(())
 }
 
 // let vec = Json::import::<Vec<f32>>("./price/close/height.json")?;
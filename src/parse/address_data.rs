@@ -1,6 +1,6 @@
 use savefile_derive::Savefile;
 
-use crate::bitcoin::sats_to_btc;
+use crate::bitcoin::sats_to_btc_f64;
 
 use super::{AddressType, EmptyAddressData, LiquidityClassification};
 
@@ -10,7 +10,7 @@ pub struct AddressData {
     pub amount: u64,
     pub sent: u64,
     pub received: u64,
-    pub mean_price_paid: f32,
+    pub mean_price_paid: f64,
     pub outputs_len: u32,
 }
 
@@ -38,11 +38,11 @@ impl AddressData {
         let previous_sat_amount = self.amount;
         let new_sat_amount = previous_sat_amount + sat_amount;
 
-        let btc_amount = sats_to_btc(sat_amount);
-        let priced_btc_value = btc_amount * price;
+        let btc_amount = sats_to_btc_f64(sat_amount);
+        let priced_btc_value = btc_amount * price as f64;
 
-        let previous_btc_amount = sats_to_btc(previous_sat_amount);
-        let new_btc_amount = sats_to_btc(new_sat_amount);
+        let previous_btc_amount = sats_to_btc_f64(previous_sat_amount);
+        let new_btc_amount = sats_to_btc_f64(new_sat_amount);
 
         self.mean_price_paid =
             (previous_mean_price_paid * previous_btc_amount + priced_btc_value) / new_btc_amount;
@@ -54,17 +54,17 @@ impl AddressData {
         self.outputs_len += 1;
     }
 
-    pub fn spend(&mut self, sat_amount: u64, price: f32) -> f32 {
+    pub fn spend(&mut self, sat_amount: u64, price: f32) -> f64 {
         let previous_mean_price_paid = self.mean_price_paid;
 
         let previous_sat_amount = self.amount;
         let new_sat_amount = previous_sat_amount - sat_amount;
 
-        let btc_value = sats_to_btc(sat_amount);
-        let priced_btc_value = btc_value * price;
+        let btc_value = sats_to_btc_f64(sat_amount);
+        let priced_btc_value = btc_value * price as f64;
 
-        let previous_btc_amount = sats_to_btc(previous_sat_amount);
-        let new_btc_amount = sats_to_btc(new_sat_amount);
+        let previous_btc_amount = sats_to_btc_f64(previous_sat_amount);
+        let new_btc_amount = sats_to_btc_f64(new_sat_amount);
 
         self.mean_price_paid =
             ((previous_mean_price_paid * previous_btc_amount) - priced_btc_value) / new_btc_amount;
@@ -4,8 +4,8 @@ use super::AddressData;
 pub struct AddressRealizedData {
     pub received: u64,
     pub sent: u64,
-    pub profit: f32,
-    pub loss: f32,
+    pub profit: f64,
+    pub loss: f64,
     pub utxos_created: u32,
     pub utxos_destroyed: u32,
     pub initial_address_data: AddressData,
@@ -29,7 +29,7 @@ impl AddressRealizedData {
         self.utxos_created += 1;
     }
 
-    pub fn send(&mut self, sats: u64, realized_profit_or_loss: f32) {
+    pub fn send(&mut self, sats: u64, realized_profit_or_loss: f64) {
         self.sent += sats;
         self.utxos_destroyed += 1;
 
@@ -4,14 +4,25 @@ pub trait AnyMap {
 
     fn t_name(&self) -> &str;
 
+    /// Values that aren't safe yet and thus exported apart, recomputed until they're buried
+    fn path_provisional(&self) -> Option<&str> {
+        None
+    }
+
     fn exported_path_with_t_name(&self) -> Vec<(&str, &str)> {
         let t_name = self.t_name();
 
+        let mut vec = vec![(self.path(), t_name)];
+
         if let Some(path_last) = self.path_last() {
-            vec![(self.path(), t_name), (path_last, t_name)]
-        } else {
-            vec![(self.path(), t_name)]
+            vec.push((path_last, t_name));
         }
+
+        if let Some(path_provisional) = self.path_provisional() {
+            vec.push((path_provisional, t_name));
+        }
+
+        vec
     }
 
     fn reset(&mut self) -> color_eyre::Result<()>;
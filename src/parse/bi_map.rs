@@ -76,6 +76,10 @@ pub trait AnyBiMap {
     fn get_height(&self) -> &(dyn AnyHeightMap + Send + Sync);
 
     fn get_date(&self) -> &(dyn AnyDateMap + Send + Sync);
+
+    fn get_mut_height(&mut self) -> &mut dyn AnyHeightMap;
+
+    fn get_mut_date(&mut self) -> &mut dyn AnyDateMap;
 }
 
 impl<T> AnyBiMap for BiMap<T>
@@ -113,4 +117,55 @@ where
     fn get_date(&self) -> &(dyn AnyDateMap + Send + Sync) {
         &self.date
     }
+
+    fn get_mut_height(&mut self) -> &mut dyn AnyHeightMap {
+        &mut self.height
+    }
+
+    fn get_mut_date(&mut self) -> &mut dyn AnyDateMap {
+        &mut self.date
+    }
+}
+
+/// Maps of values in dollars, which get a parallel map in each currency of the config
+pub trait AnyDollarBiMap: AnyBiMap {
+    /// Path given to the constructor
+    fn path(&self) -> &str;
+
+    /// From memory only, `None` if nothing was inserted at `height` while parsing
+    fn get_height_in_dollars(&self, height: usize) -> Option<f64>;
+
+    /// From memory only, `None` if nothing was inserted at `date` while parsing
+    fn get_date_in_dollars(&self, date: NaiveDate) -> Option<f64>;
+}
+
+impl<T> AnyDollarBiMap for BiMap<T>
+where
+    T: Clone
+        + Copy
+        + Default
+        + Debug
+        + Serialize
+        + DeserializeOwned
+        + Sum
+        + savefile::Serialize
+        + savefile::Deserialize
+        + savefile::ReprC
+        + Send
+        + Sync
+        + Into<f64>,
+{
+    fn path(&self) -> &str {
+        let path = self.height.as_any_map().path();
+
+        path.strip_suffix("/height").unwrap_or(path)
+    }
+
+    fn get_height_in_dollars(&self, height: usize) -> Option<f64> {
+        self.height.get(&height).map(Into::into)
+    }
+
+    fn get_date_in_dollars(&self, date: NaiveDate) -> Option<f64> {
+        self.date.get(date).map(Into::into)
+    }
 }
@@ -1,10 +1,12 @@
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Debug,
-    fs,
+    fs, mem,
+    path::Path,
 };
 
 use derive_deref::{Deref, DerefMut};
+use savefile_derive::Savefile;
 
 // https://docs.rs/sanakirja/latest/sanakirja/index.html
 // https://pijul.org/posts/2021-02-06-rethinking-sanakirja/
@@ -15,7 +17,10 @@ use sanakirja::{
     direct_repr, Commit, Env, Error, MutTxn, RootDb, Storable, UnsizedStorable,
 };
 
-use crate::io::OUTPUTS_FOLDER_PATH;
+use crate::{
+    config::Config,
+    io::{journal_path, split_height_extension, Binary, JOURNAL_EXTENSION},
+};
 
 #[allow(unused)]
 pub type SizedDatabase<Key, Value> = Database<Key, Key, Value, page::Page<Key, Value>>;
@@ -36,6 +41,14 @@ where
     db: Db_<KeyDB, Value, Page>,
     txn: MutTxn<Env, ()>,
     key_tree_to_key_db: fn(&KeyTree) -> &KeyDB,
+    path: String,
+}
+
+/// Cached changes staged on disk before a checkpoint, applied once it's committed
+#[derive(Savefile, Debug)]
+struct Journal<KeyTree, Value> {
+    dels: Vec<KeyTree>,
+    puts: Vec<(KeyTree, Value)>,
 }
 
 pub const SANAKIRJA_MAX_KEY_SIZE: usize = 510;
@@ -44,9 +57,10 @@ const PAGE_SIZE: u64 = 4096 * 256; // 1mo - Must be a multiplier of 4096
 
 impl<KeyDB, KeyTree, Value, Page> Database<KeyTree, KeyDB, Value, Page>
 where
-    KeyTree: Ord + Clone + Debug,
+    KeyTree: Ord + Clone + Debug + savefile::Serialize + savefile::Deserialize + savefile::ReprC,
     KeyDB: Ord + ?Sized + Storable,
-    Value: Copy + Storable + PartialEq,
+    Value:
+        Copy + Storable + PartialEq + savefile::Serialize + savefile::Deserialize + savefile::ReprC,
     Page: BTreeMutPage<KeyDB, Value>,
 {
     pub fn open(
@@ -54,11 +68,41 @@ where
         file: &str,
         key_tree_to_key_db: fn(&KeyTree) -> &KeyDB,
     ) -> color_eyre::Result<Self> {
+        let path = format!("{}/{file}", databases_folder_path(folder));
+
+        let journals = Self::list_journals(&path)?;
+
+        // Left by a save that was interrupted after its checkpoint was committed
+        if !journals.is_empty() {
+            let mut txn = Self::init_txn(folder, file)?;
+
+            let mut db = Self::root_db(&mut txn);
+
+            journals
+                .iter()
+                .try_for_each(|journal| -> color_eyre::Result<()> {
+                    let Journal { dels, puts } =
+                        Binary::import::<Journal<KeyTree, Value>>(journal)?;
+
+                    Ok(Self::apply(
+                        &mut txn,
+                        &mut db,
+                        key_tree_to_key_db,
+                        dels,
+                        puts,
+                    )?)
+                })?;
+
+            txn.set_root(ROOT_DB, db.db.into());
+
+            txn.commit()?;
+
+            journals.iter().try_for_each(fs::remove_file)?;
+        }
+
         let mut txn = Self::init_txn(folder, file)?;
 
-        let db = txn
-            .root_db(ROOT_DB)
-            .unwrap_or_else(|| unsafe { btree::create_db_(&mut txn).unwrap() });
+        let db = Self::root_db(&mut txn);
 
         Ok(Self {
             cached_puts: BTreeMap::default(),
@@ -66,6 +110,7 @@ where
             db,
             txn,
             key_tree_to_key_db,
+            path,
         })
     }
 
@@ -114,40 +159,97 @@ where
         self.cached_puts.insert(key, value)
     }
 
-    pub fn export(mut self) -> color_eyre::Result<(), Error> {
+    /// Writes the cached changes in a journal, to be applied by `export` once the checkpoint at `height` is committed
+    pub fn stage(&self, height: usize) -> color_eyre::Result<()> {
         if self.cached_dels.is_empty() && self.cached_puts.is_empty() {
             return Ok(());
         }
 
-        self.cached_dels
-            .into_iter()
-            .try_for_each(|key| -> Result<(), Error> {
-                btree::del(
-                    &mut self.txn,
-                    &mut self.db,
-                    (self.key_tree_to_key_db)(&key),
-                    None,
-                )?;
+        Binary::export(
+            &journal_path(&self.path, height),
+            &Journal {
+                dels: self.cached_dels.iter().cloned().collect(),
+                puts: self
+                    .cached_puts
+                    .iter()
+                    .map(|(key, value)| (key.clone(), *value))
+                    .collect(),
+            },
+        )
+    }
 
-                Ok(())
-            })?;
+    pub fn export(mut self) -> color_eyre::Result<()> {
+        if self.cached_dels.is_empty() && self.cached_puts.is_empty() {
+            return Ok(());
+        }
+
+        Self::apply(
+            &mut self.txn,
+            &mut self.db,
+            self.key_tree_to_key_db,
+            mem::take(&mut self.cached_dels),
+            mem::take(&mut self.cached_puts),
+        )?;
 
-        self.cached_puts
+        self.txn.set_root(ROOT_DB, self.db.db.into());
+
+        self.txn.commit()?;
+
+        Self::list_journals(&self.path)?
             .into_iter()
+            .try_for_each(fs::remove_file)?;
+
+        Ok(())
+    }
+
+    fn apply(
+        txn: &mut MutTxn<Env, ()>,
+        db: &mut Db_<KeyDB, Value, Page>,
+        key_tree_to_key_db: fn(&KeyTree) -> &KeyDB,
+        dels: impl IntoIterator<Item = KeyTree>,
+        puts: impl IntoIterator<Item = (KeyTree, Value)>,
+    ) -> color_eyre::Result<(), Error> {
+        dels.into_iter().try_for_each(|key| -> Result<(), Error> {
+            btree::del(txn, db, key_tree_to_key_db(&key), None)?;
+
+            Ok(())
+        })?;
+
+        puts.into_iter()
             .try_for_each(|(key, value)| -> Result<(), Error> {
-                btree::put(
-                    &mut self.txn,
-                    &mut self.db,
-                    (self.key_tree_to_key_db)(&key),
-                    &value,
-                )?;
+                btree::put(txn, db, key_tree_to_key_db(&key), &value)?;
 
                 Ok(())
-            })?;
+            })
+    }
 
-        self.txn.set_root(ROOT_DB, self.db.db.into());
+    /// Sorted by height
+    fn list_journals(path: &str) -> color_eyre::Result<Vec<String>> {
+        let folder = Path::new(path).parent().unwrap();
+
+        if !folder.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut journals = fs::read_dir(folder)?
+            .flat_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter_map(|journal| {
+                let journal = journal.to_str()?.to_owned();
+
+                let (original, height) = split_height_extension(&journal, JOURNAL_EXTENSION)?;
+
+                (original == path).then_some((height, journal))
+            })
+            .collect::<Vec<_>>();
+
+        journals.sort();
+
+        Ok(journals.into_iter().map(|(_, journal)| journal).collect())
+    }
 
-        self.txn.commit()
+    fn root_db(txn: &mut MutTxn<Env, ()>) -> Db_<KeyDB, Value, Page> {
+        txn.root_db(ROOT_DB)
+            .unwrap_or_else(|| unsafe { btree::create_db_(txn).unwrap() })
     }
 
     fn db_get(&self, key: &KeyTree) -> Option<&Value> {
@@ -177,7 +279,9 @@ where
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut, Default, Copy)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut, Default, Copy, Savefile,
+)]
 pub struct U8x19([u8; 19]);
 direct_repr!(U8x19);
 impl From<&[u8]> for U8x19 {
@@ -188,7 +292,9 @@ impl From<&[u8]> for U8x19 {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut, Default, Copy)]
+#[derive(
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deref, DerefMut, Default, Copy, Savefile,
+)]
 pub struct U8x31([u8; 31]);
 direct_repr!(U8x31);
 impl From<&[u8]> for U8x31 {
@@ -200,5 +306,5 @@ impl From<&[u8]> for U8x31 {
 }
 
 pub fn databases_folder_path(folder: &str) -> String {
-    format!("{OUTPUTS_FOLDER_PATH}/databases/{folder}")
+    format!("{}/databases/{folder}", Config::get().outputs_path)
 }
@@ -20,14 +20,20 @@ use crate::{
     utils::ToF32,
 };
 
-use super::{AnyMap, WNaiveDate};
+use super::{AnyMap, Migration, WNaiveDate};
 
 const NUMBER_OF_UNSAFE_DATES: usize = 2;
 
 #[derive(Debug, Savefile, Serialize, Deserialize)]
 pub struct SerializedDateMap<T> {
-    version: u32,
-    map: BTreeMap<WNaiveDate, T>,
+    pub version: u32,
+    pub map: BTreeMap<WNaiveDate, T>,
+}
+
+#[derive(Debug, Savefile, Serialize, Deserialize)]
+pub struct SerializedProvisionalDateMap<T> {
+    pub version: u32,
+    pub map: BTreeMap<WNaiveDate, T>,
 }
 
 pub struct DateMap<T> {
@@ -35,6 +41,7 @@ pub struct DateMap<T> {
 
     path_all: String,
     path_last: Option<String>,
+    path_provisional: String,
 
     chunks_in_memory: usize,
 
@@ -43,8 +50,12 @@ pub struct DateMap<T> {
     initial_last_date: Option<NaiveDate>,
     initial_first_unsafe_date: Option<NaiveDate>,
 
+    /// Dates from which values aren't buried enough to be written in the chunks
+    first_provisional_date: Option<NaiveDate>,
+
     imported: BTreeMap<usize, SerializedDateMap<T>>,
     to_insert: BTreeMap<usize, BTreeMap<WNaiveDate, T>>,
+    provisional: BTreeMap<WNaiveDate, T>,
 }
 
 impl<T> DateMap<T>
@@ -103,6 +114,8 @@ where
             panic!("Should always have at least the latest chunk in memory");
         }
 
+        Migration::run(path, version).unwrap();
+
         let path = format_path(path);
 
         let path_all = format!("{path}/date");
@@ -117,11 +130,15 @@ where
             }
         };
 
+        // Not in `path_all` to be skipped by `read_dir`, nor `provisional` which is taken by the height map of a `BiMap`
+        let path_provisional = serialization.append_extension(&format!("{path}/date_provisional"));
+
         let mut s = Self {
             version,
 
             path_all,
             path_last,
+            path_provisional,
 
             chunks_in_memory,
 
@@ -130,8 +147,11 @@ where
             initial_last_date: None,
             initial_first_unsafe_date: None,
 
+            first_provisional_date: None,
+
             to_insert: BTreeMap::default(),
             imported: BTreeMap::default(),
+            provisional: BTreeMap::default(),
         };
 
         s.read_dir()
@@ -157,6 +177,15 @@ where
             last_date.checked_sub_days(Days::new(offset as u64))
         });
 
+        if let Ok(serialized) = s
+            .serialization
+            .import::<SerializedProvisionalDateMap<T>>(&s.path_provisional)
+        {
+            if serialized.version == s.version {
+                s.provisional = serialized.map;
+            }
+        }
+
         s
     }
 
@@ -185,6 +214,7 @@ where
         self.to_insert
             .get(&year)
             .and_then(|tree| tree.get(date).cloned())
+            .or_else(|| self.provisional.get(date).cloned())
             .or_else(|| {
                 self.imported
                     .get(&year)
@@ -193,6 +223,29 @@ where
             })
     }
 
+    /// Like `get` but falls back to the chunk on disk if it isn't in memory
+    pub fn read(&self, date: NaiveDate) -> color_eyre::Result<Option<T>> {
+        if let Some(value) = self.get(date) {
+            return Ok(Some(value));
+        }
+
+        let path =
+            self.serialization
+                .append_extension(&format!("{}/{}", self.path_all, date.year()));
+
+        if !self.serialization.exists(&path) {
+            return Ok(None);
+        }
+
+        let serialized = self.import(Path::new(&path))?;
+
+        Ok(serialized.map.get(&WNaiveDate::wrap(date)).cloned())
+    }
+
+    pub fn set_first_provisional_date(&mut self, date: Option<NaiveDate>) {
+        self.first_provisional_date = date;
+    }
+
     #[inline(always)]
     pub fn is_date_safe(&self, date: NaiveDate) -> bool {
         self.initial_first_unsafe_date
@@ -201,41 +254,123 @@ where
             })
     }
 
+    /// Removes every value from `date` onwards, in memory and on disk, and marks them as unsafe
+    pub fn truncate(&mut self, date: NaiveDate) -> color_eyre::Result<()> {
+        let year = date.year() as usize;
+
+        let chunks = self.read_dir();
+
+        if !self.imported.contains_key(&year) {
+            if let Some(serialized) = chunks
+                .get(&year)
+                .and_then(|path| self.import(path).ok())
+                .filter(|serialized| serialized.version == self.version)
+            {
+                self.imported.insert(year, serialized);
+            }
+        }
+
+        chunks
+            .iter()
+            .filter(|(chunk_year, _)| **chunk_year > year)
+            .try_for_each(|(_, path)| fs::remove_file(path))?;
+
+        self.imported.retain(|chunk_year, _| *chunk_year <= year);
+        self.to_insert.retain(|chunk_year, _| *chunk_year <= year);
+
+        if let Some(map) = self.to_insert.get_mut(&year) {
+            map.retain(|map_date, _| **map_date < date);
+        }
+
+        self.provisional.retain(|map_date, _| **map_date < date);
+        self.export_provisional()?;
+
+        if let Some(serialized) = self.imported.get_mut(&year) {
+            serialized.map.retain(|map_date, _| **map_date < date);
+
+            let path = chunks.get(&year).cloned().unwrap_or_else(|| {
+                PathBuf::from(
+                    self.serialization
+                        .append_extension(&format!("{}/{}", self.path_all, year)),
+                )
+            });
+
+            if serialized.map.is_empty() {
+                self.imported.remove(&year);
+
+                let path = path.to_str().unwrap();
+
+                if self.serialization.exists(path) {
+                    self.serialization.remove(path)?;
+                }
+            } else {
+                self.serialization
+                    .export(path.to_str().unwrap(), serialized)?;
+            }
+        }
+
+        self.initial_last_date = self
+            .imported
+            .values()
+            .last()
+            .and_then(|serialized| serialized.map.keys().map(|date| **date).max());
+
+        self.initial_first_unsafe_date = self
+            .initial_first_unsafe_date
+            .map(|first_unsafe_date| first_unsafe_date.min(date));
+
+        Ok(())
+    }
+
     fn read_dir(&self) -> BTreeMap<usize, PathBuf> {
         Self::_read_dir(&self.path_all, &self.serialization)
     }
 
+    fn import(&self, path: &Path) -> color_eyre::Result<SerializedDateMap<T>> {
+        self.serialization
+            .import::<SerializedDateMap<T>>(path.to_str().unwrap())
+    }
+
+    fn export_provisional(&self) -> color_eyre::Result<()> {
+        if self.provisional.is_empty() {
+            let _ = self.serialization.remove(&self.path_provisional);
+
+            return Ok(());
+        }
+
+        self.serialization.export(
+            &self.path_provisional,
+            &SerializedProvisionalDateMap {
+                version: self.version,
+                map: self.provisional.clone(),
+            },
+        )
+    }
+}
+
+impl<T> DateMap<T> {
     pub fn _read_dir(path: &str, serialization: &Serialization) -> BTreeMap<usize, PathBuf> {
         fs::read_dir(path)
             .unwrap()
             .map(|entry| entry.unwrap().path())
-            .filter(|path| {
-                let file_stem = path.file_stem().unwrap().to_str().unwrap();
-                let extension = path.extension().unwrap().to_str().unwrap();
-
-                path.is_file()
-                    && file_stem.len() == 4
-                    && file_stem.starts_with("20")
-                    && extension == serialization.to_extension()
-            })
-            .map(|path| {
-                let year = path
-                    .file_stem()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .parse::<usize>()
-                    .unwrap();
-
-                (year, path)
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let (name, file_serialization) =
+                    Serialization::split_file_name(path.file_name()?.to_str()?)?;
+
+                if file_serialization != *serialization
+                    || name.len() != 4
+                    || !name.starts_with("20")
+                {
+                    return None;
+                }
+
+                let year = name.parse::<usize>().ok()?;
+
+                Some((year, path))
             })
             .collect()
     }
-
-    fn import(&self, path: &Path) -> color_eyre::Result<SerializedDateMap<T>> {
-        self.serialization
-            .import::<SerializedDateMap<T>>(path.to_str().unwrap())
-    }
 }
 
 impl<T> AnyMap for DateMap<T>
@@ -264,18 +399,65 @@ where
     }
 
     fn reset(&mut self) -> color_eyre::Result<()> {
-        fs::remove_dir(&self.path_all)?;
+        fs::remove_dir_all(&self.path_all)?;
+        fs::create_dir_all(&self.path_all)?;
+
+        if let Some(path_last) = self.path_last.as_ref() {
+            let _ = self.serialization.remove(path_last);
+        }
 
         self.initial_last_date = None;
         self.initial_first_unsafe_date = None;
 
+        let _ = self.serialization.remove(&self.path_provisional);
+
         self.imported.clear();
         self.to_insert.clear();
+        self.provisional.clear();
 
         Ok(())
     }
 
+    fn path_provisional(&self) -> Option<&str> {
+        Some(&self.path_provisional)
+    }
+
     fn pre_export(&mut self) {
+        let first_provisional_date = self.first_provisional_date;
+
+        let is_provisional = |date: &WNaiveDate| {
+            first_provisional_date
+                .is_some_and(|first_provisional_date| **date >= first_provisional_date)
+        };
+
+        // Promote the values that got buried since the last export, unless they were recomputed
+        mem::take(&mut self.provisional)
+            .into_iter()
+            .for_each(|(date, value)| {
+                if is_provisional(&date) {
+                    self.provisional.insert(date, value);
+                } else {
+                    self.to_insert
+                        .entry(date.year() as usize)
+                        .or_default()
+                        .entry(date)
+                        .or_insert(value);
+                }
+            });
+
+        self.to_insert.iter_mut().for_each(|(_, map)| {
+            map.retain(|date, value| {
+                if is_provisional(date) {
+                    self.provisional.insert(*date, *value);
+                    false
+                } else {
+                    true
+                }
+            })
+        });
+
+        self.to_insert.retain(|_, map| !map.is_empty());
+
         self.to_insert
             .iter_mut()
             .enumerate()
@@ -313,7 +495,17 @@ where
 
                 Ok(())
             },
-        )
+        )?;
+
+        self.export_provisional()?;
+
+        if let Some(path_last) = self.path_last.as_ref() {
+            if let Some(value) = self.provisional.values().last() {
+                self.serialization.export(path_last, value)?;
+            }
+        }
+
+        Ok(())
     }
 
     fn post_export(&mut self) {
@@ -337,6 +529,12 @@ pub trait AnyDateMap: AnyMap {
 
     fn get_initial_last_date(&self) -> Option<NaiveDate>;
 
+    fn inspect(&self, date: NaiveDate) -> color_eyre::Result<Option<String>>;
+
+    fn truncate(&mut self, date: NaiveDate) -> color_eyre::Result<()>;
+
+    fn set_first_provisional_date(&mut self, date: Option<NaiveDate>);
+
     fn as_any_map(&self) -> &(dyn AnyMap + Send + Sync);
 
     fn as_any_mut_map(&mut self) -> &mut dyn AnyMap;
@@ -367,6 +565,20 @@ where
         self.initial_last_date
     }
 
+    fn inspect(&self, date: NaiveDate) -> color_eyre::Result<Option<String>> {
+        self.read(date)?
+            .map(|value| serde_json::to_string(&value).map_err(|error| error.into()))
+            .transpose()
+    }
+
+    fn truncate(&mut self, date: NaiveDate) -> color_eyre::Result<()> {
+        DateMap::truncate(self, date)
+    }
+
+    fn set_first_provisional_date(&mut self, date: Option<NaiveDate>) {
+        DateMap::set_first_provisional_date(self, date)
+    }
+
     fn as_any_map(&self) -> &(dyn AnyMap + Send + Sync) {
         self
     }
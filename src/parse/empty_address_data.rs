@@ -1,8 +1,9 @@
 use sanakirja::{direct_repr, Storable, UnsizedStorable};
+use savefile_derive::Savefile;
 
 use super::{AddressData, AddressType};
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default, Savefile)]
 pub struct EmptyAddressData {
     pub address_type: AddressType,
     pub transfered: u64,
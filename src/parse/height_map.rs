@@ -5,7 +5,7 @@ use std::{
     fs,
     iter::Sum,
     mem,
-    ops::{Add, RangeInclusive, Sub},
+    ops::{Add, Range, RangeInclusive, Sub},
     path::{Path, PathBuf},
 };
 
@@ -15,18 +15,25 @@ use savefile_derive::Savefile;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
-    bitcoin::{BLOCKS_PER_HAVLING_EPOCH, NUMBER_OF_UNSAFE_BLOCKS},
+    bitcoin::BLOCKS_PER_HAVLING_EPOCH,
+    config::Config,
     io::{format_path, Serialization},
 };
 
-use super::AnyMap;
+use super::{AnyMap, Migration};
 
 pub const HEIGHT_MAP_CHUNK_SIZE: usize = BLOCKS_PER_HAVLING_EPOCH / 16;
 
 #[derive(Debug, Savefile, Serialize, Deserialize)]
 pub struct SerializedHeightMap<T> {
-    version: u32,
-    map: Vec<T>,
+    pub version: u32,
+    pub map: Vec<T>,
+}
+
+#[derive(Debug, Savefile, Serialize, Deserialize)]
+pub struct SerializedProvisionalHeightMap<T> {
+    pub version: u32,
+    pub map: BTreeMap<usize, T>,
 }
 
 pub struct HeightMap<T>
@@ -37,6 +44,7 @@ where
 
     path_all: String,
     path_last: Option<String>,
+    path_provisional: String,
 
     chunks_in_memory: usize,
 
@@ -45,8 +53,12 @@ where
     initial_last_height: Option<usize>,
     initial_first_unsafe_height: Option<usize>,
 
+    /// Heights from which values aren't buried enough to be written in the chunks
+    first_provisional_height: Option<usize>,
+
     imported: BTreeMap<usize, SerializedHeightMap<T>>,
     to_insert: BTreeMap<usize, BTreeMap<usize, T>>,
+    provisional: BTreeMap<usize, T>,
 }
 
 impl<T> HeightMap<T>
@@ -104,6 +116,8 @@ where
             panic!("Should always have at least the latest chunk in memory");
         }
 
+        Migration::run(path, version).unwrap();
+
         let path = format_path(path);
 
         let path_all = format!("{path}/height");
@@ -118,11 +132,14 @@ where
             }
         };
 
+        let path_provisional = serialization.append_extension(&format!("{path}/provisional"));
+
         let mut s = Self {
             version,
 
             path_all,
             path_last,
+            path_provisional,
 
             chunks_in_memory,
 
@@ -131,8 +148,11 @@ where
             initial_first_unsafe_height: None,
             initial_last_height: None,
 
+            first_provisional_height: None,
+
             to_insert: BTreeMap::default(),
             imported: BTreeMap::default(),
+            provisional: BTreeMap::default(),
         };
 
         s.read_dir()
@@ -154,10 +174,19 @@ where
             .map(|(chunk_start, serialized)| chunk_start + serialized.map.len());
 
         s.initial_first_unsafe_height = s.initial_last_height.and_then(|last_height| {
-            let offset = NUMBER_OF_UNSAFE_BLOCKS - 1;
+            let offset = Config::get().unsafe_blocks - 1;
             last_height.checked_sub(offset)
         });
 
+        if let Ok(serialized) = s
+            .serialization
+            .import::<SerializedProvisionalHeightMap<T>>(&s.path_provisional)
+        {
+            if serialized.version == s.version {
+                s.provisional = serialized.map;
+            }
+        }
+
         s
     }
 
@@ -193,6 +222,7 @@ where
         self.to_insert
             .get(&chunk_start)
             .and_then(|map| map.get(&(height - chunk_start)).cloned())
+            .or_else(|| self.provisional.get(height).cloned())
             .or_else(|| {
                 self.imported
                     .get(&chunk_start)
@@ -201,11 +231,107 @@ where
             })
     }
 
+    /// Like `get` but falls back to the chunk on disk if it isn't in memory
+    pub fn read(&self, height: usize) -> color_eyre::Result<Option<T>> {
+        if let Some(value) = self.get(&height) {
+            return Ok(Some(value));
+        }
+
+        let chunk_start = Self::height_to_chunk_start(height);
+
+        let path = self.serialization.append_extension(&format!(
+            "{}/{}",
+            self.path_all,
+            Self::height_to_chunk_name(height)
+        ));
+
+        if !self.serialization.exists(&path) {
+            return Ok(None);
+        }
+
+        let serialized = self.import(Path::new(&path))?;
+
+        Ok(serialized.map.get(height - chunk_start).cloned())
+    }
+
+    pub fn set_first_provisional_height(&mut self, height: Option<usize>) {
+        self.first_provisional_height = height;
+    }
+
     #[inline(always)]
     pub fn is_height_safe(&self, height: usize) -> bool {
         self.initial_first_unsafe_height.unwrap_or(0) > height
     }
 
+    /// Removes every value from `height` onwards, in memory and on disk, and marks them as unsafe
+    pub fn truncate(&mut self, height: usize) -> color_eyre::Result<()> {
+        let chunk_start = Self::height_to_chunk_start(height);
+
+        let chunks = self.read_dir();
+
+        if !self.imported.contains_key(&chunk_start) {
+            if let Some(serialized) = chunks
+                .get(&chunk_start)
+                .and_then(|path| self.import(path).ok())
+                .filter(|serialized| serialized.version == self.version)
+            {
+                self.imported.insert(chunk_start, serialized);
+            }
+        }
+
+        chunks
+            .iter()
+            .filter(|(start, _)| **start > chunk_start)
+            .try_for_each(|(_, path)| fs::remove_file(path))?;
+
+        self.imported.retain(|start, _| *start <= chunk_start);
+        self.to_insert.retain(|start, _| *start <= chunk_start);
+        self.provisional
+            .retain(|provisional_height, _| *provisional_height < height);
+        self.export_provisional()?;
+
+        if let Some(map) = self.to_insert.get_mut(&chunk_start) {
+            map.retain(|chunk_height, _| *chunk_height < height - chunk_start);
+        }
+
+        if let Some(serialized) = self.imported.get_mut(&chunk_start) {
+            serialized.map.truncate(height - chunk_start);
+
+            let path = chunks.get(&chunk_start).cloned().unwrap_or_else(|| {
+                PathBuf::from(self.serialization.append_extension(&format!(
+                    "{}/{}",
+                    self.path_all,
+                    Self::height_to_chunk_name(chunk_start)
+                )))
+            });
+
+            if serialized.map.is_empty() {
+                self.imported.remove(&chunk_start);
+
+                let path = path.to_str().unwrap();
+
+                if self.serialization.exists(path) {
+                    self.serialization.remove(path)?;
+                }
+            } else {
+                self.serialization
+                    .export(path.to_str().unwrap(), serialized)?;
+            }
+        }
+
+        self.initial_last_height = self
+            .imported
+            .iter()
+            .last()
+            .map(|(chunk_start, serialized)| chunk_start + serialized.map.len());
+
+        self.initial_first_unsafe_height = self
+            .initial_first_unsafe_height
+            .map(|first_unsafe_height| first_unsafe_height.min(height));
+
+        Ok(())
+    }
+
     fn read_dir(&self) -> BTreeMap<usize, PathBuf> {
         Self::_read_dir(&self.path_all, &self.serialization)
     }
@@ -214,24 +340,18 @@ where
         fs::read_dir(path)
             .unwrap()
             .map(|entry| entry.unwrap().path())
-            .filter(|path| {
-                let extension = path.extension().unwrap().to_str().unwrap();
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let (name, file_serialization) =
+                    Serialization::split_file_name(path.file_name()?.to_str()?)?;
 
-                path.is_file() && extension == serialization.to_extension()
-            })
-            .map(|path| {
-                (
-                    path.file_stem()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .split("..")
-                        .next()
-                        .unwrap()
-                        .parse::<usize>()
-                        .unwrap(),
-                    path,
-                )
+                if file_serialization != *serialization {
+                    return None;
+                }
+
+                let chunk_start = name.split("..").next()?.parse::<usize>().ok()?;
+
+                Some((chunk_start, path))
             })
             .collect()
     }
@@ -240,6 +360,22 @@ where
         self.serialization
             .import::<SerializedHeightMap<T>>(path.to_str().unwrap())
     }
+
+    fn export_provisional(&self) -> color_eyre::Result<()> {
+        if self.provisional.is_empty() {
+            let _ = self.serialization.remove(&self.path_provisional);
+
+            return Ok(());
+        }
+
+        self.serialization.export(
+            &self.path_provisional,
+            &SerializedProvisionalHeightMap {
+                version: self.version,
+                map: self.provisional.clone(),
+            },
+        )
+    }
 }
 
 impl<T> AnyMap for HeightMap<T>
@@ -267,18 +403,62 @@ where
     }
 
     fn reset(&mut self) -> color_eyre::Result<()> {
-        fs::remove_dir(&self.path_all)?;
+        fs::remove_dir_all(&self.path_all)?;
+        fs::create_dir_all(&self.path_all)?;
+
+        if let Some(path_last) = self.path_last.as_ref() {
+            let _ = self.serialization.remove(path_last);
+        }
 
         self.initial_last_height = None;
         self.initial_first_unsafe_height = None;
 
+        let _ = self.serialization.remove(&self.path_provisional);
+
         self.imported.clear();
         self.to_insert.clear();
+        self.provisional.clear();
 
         Ok(())
     }
 
+    fn path_provisional(&self) -> Option<&str> {
+        Some(&self.path_provisional)
+    }
+
     fn pre_export(&mut self) {
+        let first_provisional_height = self.first_provisional_height.unwrap_or(usize::MAX);
+
+        // Promote the values that got buried since the last export, unless they were recomputed
+        mem::take(&mut self.provisional)
+            .into_iter()
+            .for_each(|(height, value)| {
+                if height >= first_provisional_height {
+                    self.provisional.insert(height, value);
+                } else {
+                    self.to_insert
+                        .entry(Self::height_to_chunk_start(height))
+                        .or_default()
+                        .entry(height % HEIGHT_MAP_CHUNK_SIZE)
+                        .or_insert(value);
+                }
+            });
+
+        self.to_insert.iter_mut().for_each(|(chunk_start, map)| {
+            map.retain(|chunk_height, value| {
+                let height = chunk_start + chunk_height;
+
+                if height >= first_provisional_height {
+                    self.provisional.insert(height, *value);
+                    false
+                } else {
+                    true
+                }
+            })
+        });
+
+        self.to_insert.retain(|_, map| !map.is_empty());
+
         self.to_insert
             .iter_mut()
             .enumerate()
@@ -330,7 +510,17 @@ where
 
                 Ok(())
             },
-        )
+        )?;
+
+        self.export_provisional()?;
+
+        if let Some(path_last) = self.path_last.as_ref() {
+            if let Some(value) = self.provisional.values().last() {
+                self.serialization.export(path_last, value)?;
+            }
+        }
+
+        Ok(())
     }
 
     fn post_export(&mut self) {
@@ -354,6 +544,14 @@ pub trait AnyHeightMap: AnyMap {
 
     fn get_initial_last_height(&self) -> Option<usize>;
 
+    fn inspect(&self, height: usize) -> color_eyre::Result<Option<String>>;
+
+    fn truncate(&mut self, height: usize) -> color_eyre::Result<()>;
+
+    fn set_first_provisional_height(&mut self, height: Option<usize>);
+
+    fn fill_default(&mut self, heights: Range<usize>);
+
     fn as_any_map(&self) -> &(dyn AnyMap + Send + Sync);
 
     fn as_any_mut_map(&mut self) -> &mut dyn AnyMap;
@@ -383,6 +581,26 @@ where
         self.initial_last_height
     }
 
+    fn inspect(&self, height: usize) -> color_eyre::Result<Option<String>> {
+        self.read(height)?
+            .map(|value| serde_json::to_string(&value).map_err(|error| error.into()))
+            .transpose()
+    }
+
+    fn truncate(&mut self, height: usize) -> color_eyre::Result<()> {
+        HeightMap::truncate(self, height)
+    }
+
+    fn set_first_provisional_height(&mut self, height: Option<usize>) {
+        HeightMap::set_first_provisional_height(self, height)
+    }
+
+    fn fill_default(&mut self, heights: Range<usize>) {
+        heights.for_each(|height| {
+            self.insert_default(height);
+        });
+    }
+
     fn as_any_map(&self) -> &(dyn AnyMap + Send + Sync) {
         self
     }
@@ -33,6 +33,16 @@ impl LiquidityClassification {
         }
     }
 
+    #[inline(always)]
+    pub fn split_f64(&self, value: f64) -> LiquiditySplitResult<f64> {
+        LiquiditySplitResult {
+            all: value,
+            illiquid: value * self.illiquid as f64,
+            liquid: value * self.liquid as f64,
+            highly_liquid: value * self.highly_liquid as f64,
+        }
+    }
+
     #[inline(always)]
     pub fn split(&self, value: f32) -> LiquiditySplitResult {
         LiquiditySplitResult {
@@ -65,11 +75,11 @@ impl LiquidityClassification {
 }
 
 #[derive(Debug, Default)]
-pub struct LiquiditySplitResult {
-    pub all: f32,
-    pub illiquid: f32,
-    pub liquid: f32,
-    pub highly_liquid: f32,
+pub struct LiquiditySplitResult<T = f32> {
+    pub all: T,
+    pub illiquid: T,
+    pub liquid: T,
+    pub highly_liquid: T,
 }
 
 #[derive(Debug, Default)]
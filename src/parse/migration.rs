@@ -0,0 +1,321 @@
+use std::{collections::BTreeMap, fmt::Debug, fs, path::Path};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::io::{format_path, Serialization};
+
+use super::{
+    SerializedDateMap, SerializedHeightMap, SerializedProvisionalDateMap,
+    SerializedProvisionalHeightMap, WNaiveDate,
+};
+
+///
+/// Change applied in place to the files of a map before it's imported, instead of having its chunks discarded
+/// and computed again because its version was bumped.
+///
+/// Only the chunks still at `from` are migrated and then left at `to`, which makes running them every time
+/// the map is created harmless and lets the migrations of a map be chained.
+/// The `last` file isn't versioned and is converted when it can't be read as `New`, like a narrower number in binary
+/// (in JSON, a number is read as any of them).
+///
+pub struct Migration {
+    /// Name of the map, the last part of the path given to its constructor, to be migrated in every dataset and cohort
+    pub name: &'static str,
+    pub from: u32,
+    pub to: u32,
+    apply: fn(&Migration, &str) -> color_eyre::Result<()>,
+}
+
+pub trait MigrationValue:
+    Copy
+    + Debug
+    + Serialize
+    + DeserializeOwned
+    + savefile::Serialize
+    + savefile::Deserialize
+    + savefile::ReprC
+{
+}
+
+impl<T> MigrationValue for T where
+    T: Copy
+        + Debug
+        + Serialize
+        + DeserializeOwned
+        + savefile::Serialize
+        + savefile::Deserialize
+        + savefile::ReprC
+{
+}
+
+/// Ordered, the migrations of a map need to be after the ones they build upon
+fn registry() -> Vec<Migration> {
+    // Monetary and accumulated series moved from f32 to f64
+    [
+        "active_cap",
+        "coinblocks_created",
+        "coinblocks_destroyed",
+        "coinblocks_stored",
+        "cointime_cap",
+        "cointime_value_created",
+        "cointime_value_destroyed",
+        "cointime_value_stored",
+        "cumulative_coinblocks_created",
+        "cumulative_coinblocks_destroyed",
+        "cumulative_coinblocks_stored",
+        "cumulative_subsidy_in_dollars",
+        "investor_cap",
+        "last_subsidy_in_dollars",
+        "realized_cap",
+        "realized_loss",
+        "realized_profit",
+        "subsidy_in_dollars",
+        "thermo_cap",
+        "total_cointime_value_created",
+        "total_cointime_value_destroyed",
+        "total_cointime_value_stored",
+        "unrealized_loss",
+        "unrealized_profit",
+        "vaulted_cap",
+    ]
+    .into_iter()
+    .map(|name| Migration::convert::<f32, f64>(name, 1, 2))
+    .collect()
+}
+
+impl Migration {
+    /// The values are converted from `Old` to `New`, to change the type of a map without losing them
+    pub fn convert<Old, New>(name: &'static str, from: u32, to: u32) -> Self
+    where
+        Old: MigrationValue,
+        New: MigrationValue + From<Old>,
+    {
+        Self {
+            name,
+            from,
+            to,
+            apply: |migration, folder| migration.convert_folder::<Old, New>(folder),
+        }
+    }
+
+    /// Runs the registered migrations of the map at `path`, as given to its constructor, up to its `version`
+    pub fn run(path: &str, version: u32) -> color_eyre::Result<()> {
+        let name = path.rsplit('/').next().unwrap_or(path);
+
+        let folder = format_path(path);
+
+        if !Path::new(&folder).exists() {
+            return Ok(());
+        }
+
+        registry()
+            .iter()
+            .filter(|migration| migration.name == name && migration.to <= version)
+            .try_for_each(|migration| (migration.apply)(migration, &folder))
+    }
+
+    fn convert_folder<Old, New>(&self, folder: &str) -> color_eyre::Result<()>
+    where
+        Old: MigrationValue,
+        New: MigrationValue + From<Old>,
+    {
+        let mut migrated = 0;
+
+        for (path, serialization) in list_serialized(&format!("{folder}/height"))? {
+            let serialized = serialization.import::<SerializedHeightMap<Old>>(&path)?;
+
+            if serialized.version != self.from {
+                continue;
+            }
+
+            serialization.export(
+                &path,
+                &SerializedHeightMap {
+                    version: self.to,
+                    map: serialized
+                        .map
+                        .into_iter()
+                        .map(New::from)
+                        .collect::<Vec<_>>(),
+                },
+            )?;
+
+            migrated += 1;
+        }
+
+        for (path, serialization) in list_serialized(&format!("{folder}/date"))? {
+            let serialized = serialization.import::<SerializedDateMap<Old>>(&path)?;
+
+            if serialized.version != self.from {
+                continue;
+            }
+
+            serialization.export(
+                &path,
+                &SerializedDateMap {
+                    version: self.to,
+                    map: serialized
+                        .map
+                        .into_iter()
+                        .map(|(date, value)| (date, New::from(value)))
+                        .collect::<BTreeMap<WNaiveDate, _>>(),
+                },
+            )?;
+
+            migrated += 1;
+        }
+
+        for (path, serialization) in list_serialized(folder)? {
+            let name = Path::new(&path)
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .and_then(Serialization::split_file_name)
+                .map(|(name, _)| name);
+
+            match name {
+                Some("provisional") => {
+                    let serialized =
+                        serialization.import::<SerializedProvisionalHeightMap<Old>>(&path)?;
+
+                    if serialized.version != self.from {
+                        continue;
+                    }
+
+                    serialization.export(
+                        &path,
+                        &SerializedProvisionalHeightMap {
+                            version: self.to,
+                            map: serialized
+                                .map
+                                .into_iter()
+                                .map(|(height, value)| (height, New::from(value)))
+                                .collect::<BTreeMap<_, _>>(),
+                        },
+                    )?;
+                }
+                Some("date_provisional") => {
+                    let serialized =
+                        serialization.import::<SerializedProvisionalDateMap<Old>>(&path)?;
+
+                    if serialized.version != self.from {
+                        continue;
+                    }
+
+                    serialization.export(
+                        &path,
+                        &SerializedProvisionalDateMap {
+                            version: self.to,
+                            map: serialized
+                                .map
+                                .into_iter()
+                                .map(|(date, value)| (date, New::from(value)))
+                                .collect::<BTreeMap<_, _>>(),
+                        },
+                    )?;
+                }
+                // Isn't versioned, converted whenever it can't be read as `New` instead of only along with
+                // the chunks, in case the previous run was interrupted between them and it
+                Some("last") if serialization.import::<New>(&path).is_err() => {
+                    let value = serialization.import::<Old>(&path)?;
+
+                    serialization.export(&path, &New::from(value))?;
+                }
+                _ => {}
+            }
+        }
+
+        if migrated > 0 {
+            println!(
+                "Migrated {migrated} chunks of {folder} from version {} to {}",
+                self.from, self.to
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Files of a folder that were exported by a `Serialization`, skipping the others (.tmp, .csv...)
+fn list_serialized(folder: &str) -> color_eyre::Result<Vec<(String, Serialization)>> {
+    if !Path::new(folder).exists() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+
+    for entry in fs::read_dir(folder)? {
+        let path = entry?.path();
+
+        let Some((_, serialization)) = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .and_then(Serialization::split_file_name)
+        else {
+            continue;
+        };
+
+        if path.is_file() {
+            files.push((path.to_str().unwrap().to_owned(), serialization));
+        }
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_version_1_chunk() {
+        let root = std::env::temp_dir().join(format!(
+            "migration{}",
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        let path = format!("{}/realized_cap", root.to_str().unwrap());
+        let folder = format_path(&path);
+
+        fs::create_dir_all(format!("{folder}/height")).unwrap();
+
+        let chunk_path = format!("{folder}/height/0..13125.json");
+        let serialization = Serialization::Json;
+
+        serialization
+            .export(
+                &chunk_path,
+                &SerializedHeightMap {
+                    version: 1,
+                    map: vec![0.5_f32, 1.25, 3.0],
+                },
+            )
+            .unwrap();
+        serialization
+            .export(&format!("{folder}/last.json"), &3.0_f32)
+            .unwrap();
+
+        Migration::run(&path, 2).unwrap();
+
+        let migrated = serialization
+            .import::<SerializedHeightMap<f64>>(&chunk_path)
+            .unwrap();
+
+        assert_eq!(migrated.version, 2);
+        assert_eq!(migrated.map, vec![0.5, 1.25, 3.0]);
+
+        // Already migrated, left as is
+        Migration::run(&path, 2).unwrap();
+
+        let migrated_again = serialization
+            .import::<SerializedHeightMap<f64>>(&chunk_path)
+            .unwrap();
+
+        assert_eq!(migrated_again.map, migrated.map);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -15,6 +15,7 @@ mod date_map;
 mod empty_address_data;
 mod height_map;
 mod liquidity;
+mod migration;
 mod partial_txout_data;
 mod tx_data;
 mod txout_index;
@@ -37,6 +38,7 @@ pub use date_map::*;
 pub use empty_address_data::*;
 pub use height_map::*;
 pub use liquidity::*;
+pub use migration::*;
 pub use partial_txout_data::*;
 pub use tx_data::*;
 pub use txout_index::*;
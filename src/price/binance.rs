@@ -6,15 +6,24 @@ use color_eyre::eyre::ContextCompat;
 use itertools::Itertools;
 use serde_json::Value;
 
-use crate::io::{Json, IMPORTS_FOLDER_PATH};
+use crate::{config::Config, io::Json};
+
+use super::{Candle, PriceSource};
 
 pub struct Binance;
 
-impl Binance {
-    pub fn read_har_file() -> color_eyre::Result<BTreeMap<u32, f32>> {
+/// Binance's candles saved from the browser, for when the ones from the API don't go back far enough
+pub struct BinanceHar;
+
+impl PriceSource for BinanceHar {
+    fn name(&self) -> &'static str {
+        "binance har"
+    }
+
+    fn fetch_1mn_candles(&self) -> color_eyre::Result<BTreeMap<u32, Candle>> {
         println!("binance: read har file");
 
-        let path_binance_har = Path::new(IMPORTS_FOLDER_PATH).join("binance.har");
+        let path_binance_har = Path::new(&Config::get().imports_path).join("binance.har");
 
         let json: BTreeMap<String, Value> =
             Json::import(path_binance_har.to_str().unwrap()).unwrap_or_default();
@@ -68,27 +77,19 @@ impl Binance {
                     .as_array()
                     .unwrap()
                     .iter()
-                    .map(|array| {
-                        let array = array.as_array().unwrap();
-
-                        let timestamp = (array.first().unwrap().as_u64().unwrap() / 1000) as u32;
-
-                        let price = array
-                            .get(4)
-                            .unwrap()
-                            .as_str()
-                            .unwrap()
-                            .parse::<f32>()
-                            .unwrap();
-
-                        (timestamp, price)
-                    })
+                    .flat_map(|array| Candle::from_row(array.as_array()?, Some(5)))
                     .collect_vec()
             })
             .collect::<BTreeMap<_, _>>())
     }
+}
+
+impl PriceSource for Binance {
+    fn name(&self) -> &'static str {
+        "binance"
+    }
 
-    pub fn fetch_1mn_prices() -> color_eyre::Result<BTreeMap<u32, f32>> {
+    fn fetch_1mn_candles(&self) -> color_eyre::Result<BTreeMap<u32, Candle>> {
         println!("binance: fetch 1mn");
 
         let body: Value = reqwest::blocking::get(
@@ -96,26 +97,12 @@ impl Binance {
         )?
         .json()?;
 
+        // [timestamp (in milliseconds), open, high, low, close, volume, ...]
         Ok(body
             .as_array()
             .context("Expect to be an array")?
             .iter()
-            .map(|value| {
-                // [timestamp, open, high, low, close, volume, ...]
-                let array = value.as_array().unwrap();
-
-                let timestamp = array.first().unwrap().as_u64().unwrap() as u32;
-
-                let price = array
-                    .get(4)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .parse::<f32>()
-                    .unwrap();
-
-                (timestamp, price)
-            })
+            .flat_map(|value| Candle::from_row(value.as_array()?, Some(5)))
             .collect::<BTreeMap<_, _>>())
     }
 }
@@ -0,0 +1,88 @@
+use chrono::NaiveDate;
+use savefile_derive::Savefile;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, Savefile)]
+pub struct Candle {
+    pub open: f32,
+    pub high: f32,
+    pub low: f32,
+    pub close: f32,
+    pub volume: Option<f32>,
+}
+
+impl Candle {
+    /// For when only the close is known, the other values are left at 0 like a missing volume
+    pub fn only_close(close: f32) -> Self {
+        Self {
+            close,
+            ..Default::default()
+        }
+    }
+
+    /// Keeps a close that was already stored, even if it differs from the one of the source, the high and low
+    /// being extended to it
+    pub fn with_close(self, close: f32) -> Self {
+        Self {
+            high: self.high.max(close),
+            low: self.low.min(close),
+            close,
+            ..self
+        }
+    }
+
+    /// Row of an exchange, `[timestamp, open, high, low, close, ...]` with numbers or strings of numbers
+    pub fn from_row(row: &[Value], volume_index: Option<usize>) -> Option<(u32, Self)> {
+        let timestamp = parse_timestamp(row.first()?)?;
+
+        let candle = Self {
+            open: parse_number(row.get(1)?)?,
+            high: parse_number(row.get(2)?)?,
+            low: parse_number(row.get(3)?)?,
+            close: parse_number(row.get(4)?)?,
+            volume: volume_index
+                .and_then(|index| row.get(index))
+                .and_then(parse_number),
+        };
+
+        Some((timestamp, candle))
+    }
+
+    /// Candle of both periods, `next` being the one right after `self`
+    pub fn merge(self, next: Self) -> Self {
+        Self {
+            open: self.open,
+            high: self.high.max(next.high),
+            low: self.low.min(next.low),
+            close: next.close,
+            volume: self.volume.zip(next.volume).map(|(a, b)| a + b),
+        }
+    }
+}
+
+/// In seconds, milliseconds or as a `YYYY-MM-DD` date
+fn parse_timestamp(value: &Value) -> Option<u32> {
+    let number = value
+        .as_u64()
+        .or_else(|| value.as_str()?.parse::<u64>().ok());
+
+    if let Some(number) = number {
+        return Some(if number > u32::MAX as u64 {
+            (number / 1000) as u32
+        } else {
+            number as u32
+        });
+    }
+
+    let date = value.as_str()?.parse::<NaiveDate>().ok()?;
+
+    Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp() as u32)
+}
+
+fn parse_number(value: &Value) -> Option<f32> {
+    value
+        .as_f64()
+        .map(|number| number as f32)
+        .or_else(|| value.as_str()?.trim().parse::<f32>().ok())
+}
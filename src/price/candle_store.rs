@@ -0,0 +1,124 @@
+use std::{collections::BTreeMap, fs, mem, path::Path};
+
+use chrono::Datelike;
+
+use crate::{config::Config, io::Binary, utils::timestamp_to_naive_date};
+
+use super::Candle;
+
+type Month = (i32, u32);
+
+/// Parsing goes forward so the oldest months are the ones let go
+const MONTHS_IN_MEMORY: usize = 3;
+
+///
+/// Every 1 minute candle used for a block or imported, by the timestamp of its start, in one file per month
+/// at `{price_path}/1mn/{year}-{month}.bin`.
+///
+/// Consulted before any price source so that parsing again from scratch gives the same prices, without network.
+/// Candles kept while parsing are only written with the datasets, each month file once per export.
+///
+#[derive(Default)]
+pub struct CandleStore {
+    months: BTreeMap<Month, BTreeMap<u32, Candle>>,
+    to_export: BTreeMap<u32, Candle>,
+}
+
+impl CandleStore {
+    pub const NAME: &'static str = "store";
+
+    pub fn get(&mut self, timestamp: u32) -> color_eyre::Result<Option<Candle>> {
+        if let Some(candle) = self.to_export.get(&timestamp) {
+            return Ok(Some(*candle));
+        }
+
+        Ok(self.month(to_month(timestamp))?.get(&timestamp).cloned())
+    }
+
+    /// Saved on the next export, unless a candle is already stored for `timestamp` by then
+    pub fn keep(&mut self, timestamp: u32, candle: Candle) {
+        self.to_export.entry(timestamp).or_insert(candle);
+    }
+
+    pub fn export(&mut self) -> color_eyre::Result<()> {
+        let candles = mem::take(&mut self.to_export);
+
+        self.insert(candles, false)?;
+
+        Ok(())
+    }
+
+    /// Candles already stored are only replaced if `overwrite`, returns the number of candles added or replaced
+    pub fn insert(
+        &mut self,
+        candles: impl IntoIterator<Item = (u32, Candle)>,
+        overwrite: bool,
+    ) -> color_eyre::Result<usize> {
+        let mut by_month: BTreeMap<Month, Vec<(u32, Candle)>> = BTreeMap::new();
+
+        candles.into_iter().for_each(|(timestamp, candle)| {
+            by_month
+                .entry(to_month(timestamp))
+                .or_default()
+                .push((timestamp, candle));
+        });
+
+        let mut inserted = 0;
+
+        for (month, candles) in by_month {
+            let stored = self.month(month)?;
+
+            let len = inserted;
+
+            candles.into_iter().for_each(|(timestamp, candle)| {
+                if (overwrite || !stored.contains_key(&timestamp))
+                    && stored.insert(timestamp, candle) != Some(candle)
+                {
+                    inserted += 1;
+                }
+            });
+
+            if inserted != len {
+                let path = month_path(month);
+
+                if let Some(parent) = Path::new(&path).parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                Binary::export(&path, stored)?;
+            }
+        }
+
+        Ok(inserted)
+    }
+
+    fn month(&mut self, month: Month) -> color_eyre::Result<&mut BTreeMap<u32, Candle>> {
+        if !self.months.contains_key(&month) {
+            if self.months.len() >= MONTHS_IN_MEMORY {
+                self.months.pop_first();
+            }
+
+            let path = month_path(month);
+
+            let candles = if Binary::exists(&path) {
+                Binary::import(&path)?
+            } else {
+                BTreeMap::new()
+            };
+
+            self.months.insert(month, candles);
+        }
+
+        Ok(self.months.get_mut(&month).unwrap())
+    }
+}
+
+fn to_month(timestamp: u32) -> Month {
+    let date = timestamp_to_naive_date(timestamp);
+
+    (date.year(), date.month())
+}
+
+fn month_path((year, month): Month) -> String {
+    format!("{}/1mn/{year}-{month:02}.bin", Config::get().price_path)
+}
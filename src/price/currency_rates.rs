@@ -0,0 +1,114 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use chrono::{Days, NaiveDate};
+use color_eyre::eyre::eyre;
+
+use crate::{config::Config, utils::timestamp_to_naive_date};
+
+use super::MissingPrice;
+
+/// Rates aren't published on weekends and holidays
+const MAX_DAYS_CARRIED: u64 = 7;
+
+///
+/// Daily rates of a currency, in dollars per unit, from `currencies/{currency}.csv` in the imports folder.
+///
+/// Each row is either `date,rate` or `date,open,high,low,close` (the close being the rate), a header being skipped.
+/// The date can be a `YYYY-MM-DD` date or a timestamp in seconds or milliseconds.
+/// The file is required and checked at startup, so that a missing one isn't only noticed once parsing is stuck on it.
+///
+pub struct CurrencyRates {
+    currency: String,
+    rates: BTreeMap<NaiveDate, f64>,
+}
+
+impl CurrencyRates {
+    pub fn import(currency: &str) -> color_eyre::Result<Self> {
+        let path = Path::new(&Config::get().imports_path)
+            .join("currencies")
+            .join(format!("{currency}.csv"));
+
+        if !path.exists() {
+            return Err(eyre!(
+                "No rates for {currency}, expected them at {}",
+                path.display()
+            ));
+        }
+
+        let mut rates = BTreeMap::new();
+
+        for (index, line) in fs::read_to_string(&path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+        {
+            let row = line.split(',').map(str::trim).collect::<Vec<_>>();
+
+            match parse_row(&row) {
+                Some((date, rate)) => {
+                    rates.insert(date, rate);
+                }
+                // Header
+                None if index == 0 => {}
+                None => return Err(eyre!("Invalid row {index} in {}: {line}", path.display())),
+            }
+        }
+
+        if rates.is_empty() {
+            return Err(eyre!("No rates for {currency} in {}", path.display()));
+        }
+
+        Ok(Self {
+            currency: currency.to_owned(),
+            rates,
+        })
+    }
+
+    /// The last known rate, if it's at most a week old, and `None` before the first one of the file
+    pub fn get(&self, date: NaiveDate) -> color_eyre::Result<Option<f64>> {
+        if self
+            .rates
+            .first_key_value()
+            .is_some_and(|(first_date, _)| date < *first_date)
+        {
+            return Ok(None);
+        }
+
+        self.rates
+            .range(..=date)
+            .next_back()
+            .filter(|(rate_date, _)| {
+                rate_date
+                    .checked_add_days(Days::new(MAX_DAYS_CARRIED))
+                    .is_some_and(|limit| date <= limit)
+            })
+            .map(|(_, rate)| Some(*rate))
+            .ok_or_else(|| {
+                MissingPrice::Currency {
+                    currency: self.currency.clone(),
+                    date,
+                }
+                .into()
+            })
+    }
+}
+
+fn parse_row(row: &[&str]) -> Option<(NaiveDate, f64)> {
+    let date = row.first()?.parse::<NaiveDate>().ok().or_else(|| {
+        let timestamp = row.first()?.parse::<u64>().ok()?;
+
+        Some(timestamp_to_naive_date(if timestamp > u32::MAX as u64 {
+            (timestamp / 1000) as u32
+        } else {
+            timestamp as u32
+        }))
+    })?;
+
+    let rate = row
+        .get(if row.len() == 2 { 1 } else { 4 })?
+        .parse::<f64>()
+        .ok()
+        .filter(|rate| *rate > 0.0)?;
+
+    Some((date, rate))
+}
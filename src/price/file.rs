@@ -0,0 +1,108 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+use serde_json::Value;
+
+use crate::{config::Config, io::Json, utils::timestamp_to_naive_date};
+
+use super::{Candle, PriceSource};
+
+///
+/// Candles from `prices.csv` or `prices.json` in the imports folder, for prices that no API has anymore.
+///
+/// Each row is `timestamp,open,high,low,close` with optionally the volume and more columns after, a header being skipped.
+/// The timestamp of the start of the candle can be in seconds, milliseconds or a `YYYY-MM-DD` date.
+/// In JSON the rows are arrays in an array, like in the responses of the exchanges.
+///
+/// Rows with a date are daily candles and the others 1 minute candles, the daily candle of a date without a row
+/// being made of all the 1 minute candles of the date.
+///
+pub struct PriceFile;
+
+impl PriceFile {
+    pub const NAME: &'static str = "file";
+
+    /// 1 minute candles by the timestamp of their start and daily candles
+    fn read_candles() -> color_eyre::Result<(BTreeMap<u32, Candle>, BTreeMap<NaiveDate, Candle>)> {
+        let imports_path = Path::new(&Config::get().imports_path);
+
+        let csv_path = imports_path.join("prices.csv");
+        let json_path = imports_path.join("prices.json");
+
+        let rows = if csv_path.exists() {
+            fs::read_to_string(csv_path)?
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| {
+                    line.split(',')
+                        .map(|cell| Value::String(cell.trim().to_owned()))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        } else if json_path.exists() {
+            Json::import::<Vec<Vec<Value>>>(json_path.to_str().unwrap())?
+        } else {
+            return Err(eyre!(
+                "No prices.csv or prices.json in {}",
+                imports_path.display()
+            ));
+        };
+
+        let mut minutes = BTreeMap::new();
+        let mut days = BTreeMap::new();
+
+        for (index, row) in rows.iter().enumerate() {
+            let date = row
+                .first()
+                .and_then(|cell| cell.as_str())
+                .and_then(|cell| cell.parse::<NaiveDate>().ok());
+
+            match (Candle::from_row(row, Some(5)), date) {
+                (Some((_, candle)), Some(date)) => {
+                    days.insert(date, candle);
+                }
+                (Some((timestamp, candle)), None) => {
+                    minutes.insert(timestamp, candle);
+                }
+                // Header
+                (None, _) if index == 0 => {}
+                (None, _) => return Err(eyre!("Invalid row {index} in prices file: {row:?}")),
+            }
+        }
+
+        Ok((minutes, days))
+    }
+}
+
+impl PriceSource for PriceFile {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn fetch_1mn_candles(&self) -> color_eyre::Result<BTreeMap<u32, Candle>> {
+        Ok(Self::read_candles()?.0)
+    }
+
+    fn fetch_daily_candles(&self) -> color_eyre::Result<BTreeMap<NaiveDate, Candle>> {
+        let (minutes, mut days) = Self::read_candles()?;
+
+        let mut merged: BTreeMap<NaiveDate, Candle> = BTreeMap::new();
+
+        minutes.into_iter().for_each(|(timestamp, candle)| {
+            let date = timestamp_to_naive_date(timestamp);
+
+            let day = merged
+                .get(&date)
+                .map_or(candle, |previous| previous.merge(candle));
+
+            merged.insert(date, day);
+        });
+
+        merged.into_iter().for_each(|(date, candle)| {
+            days.entry(date).or_insert(candle);
+        });
+
+        Ok(days)
+    }
+}
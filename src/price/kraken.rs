@@ -1,21 +1,24 @@
 #![allow(dead_code)]
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::BTreeMap;
 
+use chrono::NaiveDate;
 use color_eyre::eyre::ContextCompat;
 use serde_json::Value;
 
 use crate::utils::timestamp_to_naive_date;
 
+use super::{Candle, PriceSource};
+
 pub struct Kraken;
 
 impl Kraken {
-    pub fn fetch_1mn_prices() -> color_eyre::Result<BTreeMap<u32, f32>> {
-        println!("kraken: fetch 1mn");
-
-        let body: Value =
-            reqwest::blocking::get("https://api.kraken.com/0/public/OHLC?pair=XBTUSD&interval=1")?
-                .json()?;
+    /// `[timestamp, open, high, low, close, vwap, volume, count]` rows by their timestamp
+    fn fetch_candles(interval: usize) -> color_eyre::Result<BTreeMap<u32, Candle>> {
+        let body: Value = reqwest::blocking::get(format!(
+            "https://api.kraken.com/0/public/OHLC?pair=XBTUSD&interval={interval}"
+        ))?
+        .json()?;
 
         Ok(body
             .as_object()
@@ -29,60 +32,28 @@ impl Kraken {
             .as_array()
             .context("Expect to be an array")?
             .iter()
-            .map(|value| {
-                let array = value.as_array().unwrap();
-
-                let timestamp = array.first().unwrap().as_u64().unwrap() as u32;
-
-                let price = array
-                    .get(4)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .parse::<f32>()
-                    .unwrap();
-
-                (timestamp, price)
-            })
+            .flat_map(|value| Candle::from_row(value.as_array()?, Some(6)))
             .collect::<BTreeMap<_, _>>())
     }
+}
 
-    pub fn fetch_daily_prices() -> color_eyre::Result<HashMap<String, f32>> {
-        println!("fetch kraken daily");
-
-        let body: Value = reqwest::blocking::get(
-            "https://api.kraken.com/0/public/OHLC?pair=XBTUSD&interval=1440",
-        )?
-        .json()?;
+impl PriceSource for Kraken {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
 
-        Ok(body
-            .as_object()
-            .context("Expect to be an object")?
-            .get("result")
-            .context("Expect object to have result")?
-            .as_object()
-            .context("Expect to be an object")?
-            .get("XXBTZUSD")
-            .context("Expect to have XXBTZUSD")?
-            .as_array()
-            .context("Expect to be an array")?
-            .iter()
-            .map(|value| {
-                let array = value.as_array().unwrap();
+    fn fetch_1mn_candles(&self) -> color_eyre::Result<BTreeMap<u32, Candle>> {
+        println!("kraken: fetch 1mn");
 
-                let date = timestamp_to_naive_date(array.first().unwrap().as_u64().unwrap() as u32)
-                    .to_string();
+        Self::fetch_candles(1)
+    }
 
-                let price = array
-                    .get(4)
-                    .unwrap()
-                    .as_str()
-                    .unwrap()
-                    .parse::<f32>()
-                    .unwrap();
+    fn fetch_daily_candles(&self) -> color_eyre::Result<BTreeMap<NaiveDate, Candle>> {
+        println!("fetch kraken daily");
 
-                (date, price)
-            })
-            .collect::<HashMap<_, _>>())
+        Ok(Self::fetch_candles(1440)?
+            .into_iter()
+            .map(|(timestamp, candle)| (timestamp_to_naive_date(timestamp), candle))
+            .collect())
     }
 }
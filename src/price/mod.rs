@@ -1,5 +1,17 @@
 mod binance;
+mod candle;
+mod candle_store;
+mod currency_rates;
+mod file;
 mod kraken;
+mod quarantine;
+mod source;
 
 pub use binance::*;
+pub use candle::*;
+pub use candle_store::*;
+pub use currency_rates::*;
+pub use file::*;
 pub use kraken::*;
+pub use quarantine::*;
+pub use source::*;
@@ -0,0 +1,173 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config::Config, io::Json};
+
+use super::Candle;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedCandle {
+    pub timestamp: u32,
+    pub source: String,
+    pub candle: Candle,
+    pub reasons: Vec<String>,
+    /// Set by hand to use the candle anyway
+    #[serde(default)]
+    pub accepted: bool,
+}
+
+///
+/// Candles of heights that didn't pass the checks, by height, at `{price_path}/quarantine.json`.
+///
+/// Parsing stops at the first one, until it's accepted or another source (like a prices file) has a price that passes.
+///
+#[derive(Default)]
+pub struct Quarantine {
+    candles: BTreeMap<usize, QuarantinedCandle>,
+}
+
+impl Quarantine {
+    pub fn path() -> String {
+        format!("{}/quarantine.json", Config::get().price_path)
+    }
+
+    pub fn import() -> Self {
+        let path = Self::path();
+
+        let candles = if Path::new(&path).exists() {
+            Json::import(&path).unwrap_or_else(|error| {
+                println!("Failed to import {path}: {error}");
+                BTreeMap::new()
+            })
+        } else {
+            BTreeMap::new()
+        };
+
+        Self { candles }
+    }
+
+    /// Removes the candle of the height from the quarantine if it was accepted
+    pub fn take_accepted(&mut self, height: usize) -> color_eyre::Result<Option<Candle>> {
+        if !self
+            .candles
+            .get(&height)
+            .is_some_and(|quarantined| quarantined.accepted)
+        {
+            return Ok(None);
+        }
+
+        let candle = self
+            .candles
+            .remove(&height)
+            .map(|quarantined| quarantined.candle);
+
+        self.export()?;
+
+        Ok(candle)
+    }
+
+    pub fn insert(
+        &mut self,
+        height: usize,
+        quarantined: QuarantinedCandle,
+    ) -> color_eyre::Result<()> {
+        self.candles.insert(height, quarantined);
+
+        self.export()
+    }
+
+    /// For when a candle of the height passed the checks after all
+    pub fn remove(&mut self, height: usize) -> color_eyre::Result<()> {
+        if self.candles.remove(&height).is_some() {
+            self.export()?;
+        }
+
+        Ok(())
+    }
+
+    fn export(&self) -> color_eyre::Result<()> {
+        Json::export(&Self::path(), &self.candles)
+    }
+}
+
+///
+/// Reasons not to trust a close, compared to the closes of the other sources and to the previous block.
+///
+/// `max_deviation` and `max_gap` are in percent like in the config, 0 disabling their check.
+///
+pub fn check_close(
+    close: f32,
+    other_closes: &[(&str, f32)],
+    previous_close: Option<f32>,
+    max_deviation: f32,
+    max_gap: f32,
+) -> Vec<String> {
+    if !close.is_finite() || close <= 0.0 {
+        return vec![format!("{close} isn't a price")];
+    }
+
+    let percentage_away = |other: f32| ((close - other) / other).abs() * 100.0;
+
+    let mut reasons = vec![];
+
+    if max_deviation > 0.0 {
+        other_closes
+            .iter()
+            .filter(|(_, other)| percentage_away(*other) > max_deviation)
+            .for_each(|(name, other)| {
+                reasons.push(format!(
+                    "{name} has {other}, {:.1}% away",
+                    percentage_away(*other)
+                ))
+            });
+    }
+
+    if max_gap > 0.0 {
+        if let Some(previous_close) = previous_close {
+            let gap = percentage_away(previous_close);
+
+            // A big move seen by another source is real
+            let is_confirmed = other_closes
+                .iter()
+                .any(|(_, other)| max_deviation == 0.0 || percentage_away(*other) <= max_deviation);
+
+            if gap > max_gap && !is_confirmed {
+                reasons.push(format!(
+                    "{gap:.1}% away from the previous block ({previous_close})"
+                ));
+            }
+        }
+    }
+
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_close() {
+        // Close to the other source and to the previous block
+        assert!(check_close(100.0, &[("kraken", 101.0)], Some(99.0), 5.0, 20.0).is_empty());
+
+        // Deviates from a source
+        let reasons = check_close(100.0, &[("kraken", 110.0)], Some(99.0), 5.0, 20.0);
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].starts_with("kraken has 110"));
+
+        // Big move that no other source has
+        let reasons = check_close(150.0, &[], Some(100.0), 5.0, 20.0);
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].starts_with("50.0% away from the previous block"));
+
+        // Same move confirmed by another source
+        assert!(check_close(150.0, &[("kraken", 148.0)], Some(100.0), 5.0, 20.0).is_empty());
+
+        // Disabled checks
+        assert!(check_close(150.0, &[("kraken", 110.0)], Some(100.0), 0.0, 0.0).is_empty());
+
+        assert!(!check_close(f32::NAN, &[], None, 5.0, 20.0).is_empty());
+    }
+}
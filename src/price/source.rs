@@ -0,0 +1,171 @@
+use std::{collections::BTreeMap, error, fmt};
+
+use chrono::{NaiveDate, Utc};
+use color_eyre::eyre::eyre;
+
+use crate::config::{Config, PriceSourceKind};
+
+use super::{Binance, BinanceHar, Candle, CandleStore, Kraken, PriceFile, Quarantine};
+
+pub trait PriceSource {
+    fn name(&self) -> &'static str;
+
+    /// 1 minute candles, by the timestamp (in seconds) of their start
+    fn fetch_1mn_candles(&self) -> color_eyre::Result<BTreeMap<u32, Candle>>;
+
+    /// Only some sources have daily candles
+    fn fetch_daily_candles(&self) -> color_eyre::Result<BTreeMap<NaiveDate, Candle>> {
+        Err(eyre!("{} doesn't have daily candles", self.name()))
+    }
+}
+
+///
+/// Sources tried in the order of the config until one has the candle, after the candle store.
+///
+/// Each one is only fetched once, the first time it's needed, and one that failed is then skipped.
+/// Only the candles that passed the checks of the quarantine are saved in the store.
+///
+pub struct PriceSources {
+    store: CandleStore,
+    sources: Vec<Box<dyn PriceSource + Send + Sync>>,
+    minutes: Vec<Option<BTreeMap<u32, Candle>>>,
+    days: Vec<Option<BTreeMap<NaiveDate, Candle>>>,
+}
+
+impl PriceSources {
+    pub fn new() -> Self {
+        let sources = Config::get()
+            .price_sources
+            .iter()
+            .map(|kind| -> Box<dyn PriceSource + Send + Sync> {
+                match kind {
+                    PriceSourceKind::File => Box::new(PriceFile),
+                    PriceSourceKind::Kraken => Box::new(Kraken),
+                    PriceSourceKind::Binance => Box::new(Binance),
+                    PriceSourceKind::Har => Box::new(BinanceHar),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Self {
+            store: CandleStore::default(),
+            minutes: sources.iter().map(|_| None).collect(),
+            days: sources.iter().map(|_| None).collect(),
+            sources,
+        }
+    }
+
+    /// `timestamp` needs to be the start of a minute, returns the candle with the name of where it was found
+    pub fn get_1mn(
+        &mut self,
+        timestamp: u32,
+    ) -> color_eyre::Result<Option<(&'static str, Candle)>> {
+        if let Some(candle) = self.store.get(timestamp)? {
+            return Ok(Some((CandleStore::NAME, candle)));
+        }
+
+        Ok((0..self.sources.len()).find_map(|index| {
+            let name = self.sources[index].name();
+
+            self.minutes(index)
+                .get(&timestamp)
+                .map(|candle| (name, *candle))
+        }))
+    }
+
+    /// Closes of the minute in every source that has it, to compare them
+    pub fn get_1mn_closes(&mut self, timestamp: u32) -> Vec<(&'static str, f32)> {
+        (0..self.sources.len())
+            .filter_map(|index| {
+                let name = self.sources[index].name();
+
+                self.minutes(index)
+                    .get(&timestamp)
+                    .map(|candle| (name, candle.close))
+            })
+            .collect()
+    }
+
+    /// Saves a candle that passed the checks in the store, if its minute is over
+    pub fn keep_1mn(&mut self, timestamp: u32, candle: Candle) {
+        if timestamp + 60 <= Utc::now().timestamp() as u32 {
+            self.store.keep(timestamp, candle);
+        }
+    }
+
+    /// Writes the candles kept since the last export
+    pub fn export(&mut self) -> color_eyre::Result<()> {
+        self.store.export()
+    }
+
+    fn minutes(&mut self, index: usize) -> &BTreeMap<u32, Candle> {
+        let source = &self.sources[index];
+
+        self.minutes[index]
+            .get_or_insert_with(|| fetch(source.name(), || source.fetch_1mn_candles()))
+    }
+
+    pub fn get_daily(&mut self, date: NaiveDate) -> Option<Candle> {
+        self.sources
+            .iter()
+            .zip(self.days.iter_mut())
+            .find_map(|(source, days)| {
+                days.get_or_insert_with(|| fetch(source.name(), || source.fetch_daily_candles()))
+                    .get(&date)
+                    .cloned()
+            })
+    }
+}
+
+fn fetch<K>(
+    name: &str,
+    fetch: impl FnOnce() -> color_eyre::Result<BTreeMap<K, Candle>>,
+) -> BTreeMap<K, Candle> {
+    fetch().unwrap_or_else(|error| {
+        println!("{name}: {error}");
+        BTreeMap::new()
+    })
+}
+
+/// None of the sources had a price that can be trusted, parsing can't go further until one does
+#[derive(Debug, Clone)]
+pub enum MissingPrice {
+    Height { height: usize, timestamp: u32 },
+    Date(NaiveDate),
+    Currency { currency: String, date: NaiveDate },
+    Quarantined { height: usize, reasons: Vec<String> },
+}
+
+impl fmt::Display for MissingPrice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sources = Config::get()
+            .price_sources
+            .iter()
+            .map(|kind| format!("{kind:?}").to_lowercase())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self {
+            Self::Height { height, timestamp } => write!(
+                f,
+                "Can't find the price of height {height} (timestamp: {timestamp}) in any source ({sources}), please add it to a prices file or update binance.har"
+            ),
+            Self::Date(date) => write!(
+                f,
+                "Can't find the price of {date} in any source ({sources}), please add it to a prices file"
+            ),
+            Self::Currency { currency, date } => write!(
+                f,
+                "Can't find the rate of {currency} on {date} (or the week before), please add it to currencies/{currency}.csv"
+            ),
+            Self::Quarantined { height, reasons } => write!(
+                f,
+                "The price of height {height} was quarantined ({}), please accept it in {} or add the right one to a prices file",
+                reasons.join(", "),
+                Quarantine::path()
+            ),
+        }
+    }
+}
+
+impl error::Error for MissingPrice {}
@@ -0,0 +1,190 @@
+mod query;
+mod reader;
+
+use std::{collections::BTreeMap, io::Cursor, thread, time::UNIX_EPOCH};
+
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+use serde_json::Value;
+use tiny_http::{Header, Request, Response, Server, StatusCode};
+
+use query::*;
+pub use reader::*;
+
+use crate::{
+    config::Config,
+    io::{relative_map_path, Json},
+};
+
+const MAX_AGE_FINAL: usize = 60 * 60 * 24;
+const MAX_AGE: usize = 60;
+/// Requests are handled by that many threads, the others waiting in the queue of the server
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+///
+/// Serves the datasets under `/api/<path>?from=&to=&format=json|csv`,
+/// `<path>` being the path of a height or date map relative to the datasets folder,
+/// or to the price folder prefixed by `price/`.
+///
+/// `/api` lists every available path with its type, as found in `paths.json`.
+///
+pub fn serve() -> color_eyre::Result<()> {
+    let config = Config::get();
+
+    let server = Server::http(&config.api_address).map_err(|error| eyre!("{error}"))?;
+
+    println!(
+        "Serving {} on http://{}/api",
+        config.datasets_path, config.api_address
+    );
+
+    thread::scope(|scope| {
+        (0..MAX_CONCURRENT_REQUESTS).for_each(|_| {
+            scope.spawn(|| {
+                for request in server.incoming_requests() {
+                    let response = handle(&request).unwrap_or_else(|(status, error)| {
+                        text_response(status, &error.to_string())
+                    });
+
+                    let _ = request.respond(response);
+                }
+            });
+        });
+    });
+
+    Ok(())
+}
+
+type HandleResult = Result<Response<Cursor<Vec<u8>>>, (u16, color_eyre::Report)>;
+
+fn handle(request: &Request) -> HandleResult {
+    let url = request.url();
+
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    let path = path
+        .strip_prefix("/api")
+        .ok_or((404, eyre!("Not found")))?
+        .trim_matches('/');
+
+    let registry = import_registry().map_err(|error| (500, error))?;
+
+    if path.is_empty() {
+        let path_to_type = registry
+            .iter()
+            .map(|(path, (_, t_name))| (path, t_name))
+            .collect::<BTreeMap<_, _>>();
+
+        let body = serde_json::to_vec(&path_to_type).map_err(|error| (500, error.into()))?;
+
+        return Ok(response(200, "application/json", body));
+    }
+
+    let (full_path, t_name) = registry
+        .get(path)
+        .ok_or((404, eyre!("Unknown path: {path}")))?;
+
+    let query = Query::parse(query).map_err(|error| (400, error))?;
+
+    let (values, key_name) = if path.ends_with("/height") {
+        let (from, to) = query.bounds::<usize>().map_err(|error| (400, error))?;
+
+        (read_height_map(full_path, t_name, from, to), "height")
+    } else if path.ends_with("/date") {
+        let (from, to) = query.bounds::<NaiveDate>().map_err(|error| (400, error))?;
+
+        (read_date_map(full_path, t_name, from, to), "date")
+    } else {
+        return Err((404, eyre!("Not a map: {path}")));
+    };
+
+    let values = values.map_err(|error| (500, error))?;
+
+    let etag = values.last_modified.map(|last_modified| {
+        let since_epoch = last_modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        format!("\"{:x}-{:x}\"", since_epoch.as_nanos(), values.rows.len())
+    });
+
+    let is_not_modified = etag.as_ref().is_some_and(|etag| {
+        request.headers().iter().any(|header| {
+            header.field.equiv("If-None-Match") && header.value.as_str() == etag.as_str()
+        })
+    });
+
+    let mut response = if is_not_modified {
+        response(304, query.format.content_type(), vec![])
+    } else {
+        let body = match query.format {
+            Format::Json => {
+                serde_json::to_vec(&values.rows).map_err(|error| (500, error.into()))?
+            }
+            Format::Csv => to_csv(key_name, &values.rows).into_bytes(),
+        };
+
+        response(200, query.format.content_type(), body)
+    };
+
+    if let Some(etag) = etag {
+        response.add_header(header("ETag", &etag));
+    }
+
+    let max_age = if values.is_final {
+        MAX_AGE_FINAL
+    } else {
+        MAX_AGE
+    };
+
+    response.add_header(header(
+        "Cache-Control",
+        &format!("public, max-age={max_age}"),
+    ));
+
+    Ok(response)
+}
+
+/// Paths relative to the datasets or price folder and only of maps, with their full path and type
+fn import_registry() -> color_eyre::Result<BTreeMap<String, (String, String)>> {
+    let paths = Json::import::<BTreeMap<String, String>>(&format!(
+        "{}/paths.json",
+        Config::get().datasets_path
+    ))?;
+
+    Ok(paths
+        .into_iter()
+        .filter(|(full_path, _)| full_path.ends_with("/height") || full_path.ends_with("/date"))
+        .filter_map(|(full_path, t_name)| {
+            Some((relative_map_path(&full_path)?, (full_path, t_name)))
+        })
+        .collect())
+}
+
+fn to_csv(key_name: &str, rows: &[(Value, Value)]) -> String {
+    let to_cell = |value: &Value| match value {
+        Value::String(string) => string.to_owned(),
+        value => value.to_string(),
+    };
+
+    let mut csv = format!("{key_name},value\n");
+
+    rows.iter().for_each(|(key, value)| {
+        csv += &format!("{},{}\n", to_cell(key), to_cell(value));
+    });
+
+    csv
+}
+
+fn response(status: u16, content_type: &str, body: Vec<u8>) -> Response<Cursor<Vec<u8>>> {
+    Response::from_data(body)
+        .with_status_code(StatusCode(status))
+        .with_header(header("Content-Type", content_type))
+        .with_header(header("Access-Control-Allow-Origin", "*"))
+}
+
+fn text_response(status: u16, text: &str) -> Response<Cursor<Vec<u8>>> {
+    response(status, "text/plain", text.as_bytes().to_vec())
+}
+
+fn header(field: &str, value: &str) -> Header {
+    Header::from_bytes(field.as_bytes(), value.as_bytes()).unwrap()
+}
@@ -0,0 +1,104 @@
+use std::str::FromStr;
+
+use color_eyre::eyre::eyre;
+use url::form_urlencoded;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn content_type(&self) -> &str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(eyre!("Unknown format \"{s}\", expected json or csv")),
+        }
+    }
+}
+
+/// `?from=&to=&format=`, bounds are both inclusive and either heights or dates depending on the map
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub format: Format,
+}
+
+impl Query {
+    pub fn parse(query: &str) -> color_eyre::Result<Self> {
+        let mut s = Self::default();
+
+        for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "from" => s.from = Some(value.into_owned()),
+                "to" => s.to = Some(value.into_owned()),
+                "format" => s.format = value.parse()?,
+                _ => return Err(eyre!("Unknown parameter \"{key}\"")),
+            }
+        }
+
+        Ok(s)
+    }
+
+    pub fn bounds<K>(&self) -> color_eyre::Result<(Option<K>, Option<K>)>
+    where
+        K: FromStr,
+    {
+        let parse = |bound: &Option<String>| {
+            bound
+                .as_ref()
+                .map(|bound| {
+                    bound
+                        .parse::<K>()
+                        .map_err(|_| eyre!("Invalid bound \"{bound}\""))
+                })
+                .transpose()
+        };
+
+        Ok((parse(&self.from)?, parse(&self.to)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_query() {
+        assert_eq!(Query::parse("").unwrap(), Query::default());
+
+        let query = Query::parse("from=840000&to=840100&format=csv").unwrap();
+        assert_eq!(query.format, Format::Csv);
+        assert_eq!(
+            query.bounds::<usize>().unwrap(),
+            (Some(840_000), Some(840_100))
+        );
+        assert!(query.bounds::<NaiveDate>().is_err());
+
+        let query = Query::parse("from=2024-04-20").unwrap();
+        assert_eq!(
+            query.bounds::<NaiveDate>().unwrap(),
+            (NaiveDate::from_ymd_opt(2024, 4, 20), None)
+        );
+
+        assert!(Query::parse("format=xml").is_err());
+        assert!(Query::parse("limit=10").is_err());
+    }
+}
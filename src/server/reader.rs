@@ -0,0 +1,247 @@
+use std::{
+    any::type_name,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use chrono::{Datelike, NaiveDate};
+use color_eyre::eyre::eyre;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{
+    io::Serialization,
+    parse::{
+        DateMap, HeightMap, SerializedDateMap, SerializedHeightMap, SerializedProvisionalDateMap,
+        SerializedProvisionalHeightMap, WNaiveDate, HEIGHT_MAP_CHUNK_SIZE,
+    },
+};
+
+#[derive(Debug, Default)]
+pub struct Values {
+    pub rows: Vec<(Value, Value)>,
+    pub last_modified: Option<SystemTime>,
+    /// `false` if some values can still change, in the last chunk or the provisional layer
+    pub is_final: bool,
+}
+
+impl Values {
+    fn read_from(&mut self, path: &Path) -> color_eyre::Result<()> {
+        let modified = fs::metadata(path)?.modified()?;
+
+        self.last_modified = self.last_modified.max(Some(modified));
+
+        Ok(())
+    }
+
+    fn push<K, T>(&mut self, key: K, value: T) -> color_eyre::Result<()>
+    where
+        K: Serialize,
+        T: Serialize,
+    {
+        self.rows
+            .push((serde_json::to_value(key)?, serde_json::to_value(value)?));
+
+        Ok(())
+    }
+}
+
+pub fn read_height_map(
+    path: &str,
+    t_name: &str,
+    from: Option<usize>,
+    to: Option<usize>,
+) -> color_eyre::Result<Values> {
+    match t_name {
+        "f32" => _read_height_map::<f32>(path, from, to),
+        "f64" => _read_height_map::<f64>(path, from, to),
+        "u32" => _read_height_map::<u32>(path, from, to),
+        "u64" => _read_height_map::<u64>(path, from, to),
+        "usize" => _read_height_map::<usize>(path, from, to),
+        t_name if t_name == type_name::<WNaiveDate>() => {
+            _read_height_map::<WNaiveDate>(path, from, to)
+        }
+        _ => Err(eyre!("Unsupported type: {t_name}")),
+    }
+}
+
+pub fn read_date_map(
+    path: &str,
+    t_name: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> color_eyre::Result<Values> {
+    match t_name {
+        "f32" => _read_date_map::<f32>(path, from, to),
+        "f64" => _read_date_map::<f64>(path, from, to),
+        "u32" => _read_date_map::<u32>(path, from, to),
+        "u64" => _read_date_map::<u64>(path, from, to),
+        "usize" => _read_date_map::<usize>(path, from, to),
+        t_name if t_name == type_name::<WNaiveDate>() => {
+            _read_date_map::<WNaiveDate>(path, from, to)
+        }
+        _ => Err(eyre!("Unsupported type: {t_name}")),
+    }
+}
+
+fn _read_height_map<T>(
+    path: &str,
+    from: Option<usize>,
+    to: Option<usize>,
+) -> color_eyre::Result<Values>
+where
+    T: Clone
+        + Copy
+        + Default
+        + Debug
+        + Serialize
+        + DeserializeOwned
+        + savefile::Serialize
+        + savefile::Deserialize
+        + savefile::ReprC,
+{
+    let mut values = Values {
+        is_final: true,
+        ..Default::default()
+    };
+
+    let Some(serialization) = Serialization::find(path)? else {
+        return Ok(values);
+    };
+
+    let is_in_range =
+        |height: usize| from.is_none_or(|from| from <= height) && to.is_none_or(|to| height <= to);
+
+    let chunks = HeightMap::<T>::_read_dir(path, &serialization);
+
+    let last_chunk_start = chunks.keys().last().cloned();
+
+    let first_chunk_start = from.map_or(0, |from| {
+        from / HEIGHT_MAP_CHUNK_SIZE * HEIGHT_MAP_CHUNK_SIZE
+    });
+
+    for (chunk_start, chunk_path) in chunks.range(first_chunk_start..) {
+        if to.is_some_and(|to| *chunk_start > to) {
+            break;
+        }
+
+        values.read_from(chunk_path)?;
+
+        if Some(*chunk_start) == last_chunk_start {
+            values.is_final = false;
+        }
+
+        let serialized =
+            serialization.import::<SerializedHeightMap<T>>(chunk_path.to_str().unwrap())?;
+
+        serialized
+            .map
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| (chunk_start + index, value))
+            .filter(|(height, _)| is_in_range(*height))
+            .try_for_each(|(height, value)| values.push(height, value))?;
+    }
+
+    let provisional_path = serialization.existing_path(&serialization.append_extension(&format!(
+        "{}/provisional",
+        Path::new(path).parent().unwrap().to_str().unwrap()
+    )));
+
+    if let Some(provisional_path) = provisional_path.map(PathBuf::from) {
+        values.read_from(&provisional_path)?;
+
+        values.is_final = false;
+
+        let serialized = serialization
+            .import::<SerializedProvisionalHeightMap<T>>(provisional_path.to_str().unwrap())?;
+
+        serialized
+            .map
+            .into_iter()
+            .filter(|(height, _)| is_in_range(*height))
+            .try_for_each(|(height, value)| values.push(height, value))?;
+    }
+
+    Ok(values)
+}
+
+fn _read_date_map<T>(
+    path: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> color_eyre::Result<Values>
+where
+    T: Clone
+        + Copy
+        + Default
+        + Debug
+        + Serialize
+        + DeserializeOwned
+        + savefile::Serialize
+        + savefile::Deserialize
+        + savefile::ReprC,
+{
+    let mut values = Values {
+        is_final: true,
+        ..Default::default()
+    };
+
+    let Some(serialization) = Serialization::find(path)? else {
+        return Ok(values);
+    };
+
+    let is_in_range =
+        |date: NaiveDate| from.is_none_or(|from| from <= date) && to.is_none_or(|to| date <= to);
+
+    let chunks = DateMap::<T>::_read_dir(path, &serialization);
+
+    let last_year = chunks.keys().last().cloned();
+
+    let first_year = from.map_or(0, |from| from.year() as usize);
+
+    for (year, chunk_path) in chunks.range(first_year..) {
+        if to.is_some_and(|to| *year > to.year() as usize) {
+            break;
+        }
+
+        values.read_from(chunk_path)?;
+
+        if Some(*year) == last_year {
+            values.is_final = false;
+        }
+
+        let serialized =
+            serialization.import::<SerializedDateMap<T>>(chunk_path.to_str().unwrap())?;
+
+        serialized
+            .map
+            .into_iter()
+            .filter(|(date, _)| is_in_range(**date))
+            .try_for_each(|(date, value)| values.push(date, value))?;
+    }
+
+    let provisional_path = serialization.existing_path(&serialization.append_extension(&format!(
+        "{}/date_provisional",
+        Path::new(path).parent().unwrap().to_str().unwrap()
+    )));
+
+    if let Some(provisional_path) = provisional_path.map(PathBuf::from) {
+        values.read_from(&provisional_path)?;
+
+        values.is_final = false;
+
+        let serialized = serialization
+            .import::<SerializedProvisionalDateMap<T>>(provisional_path.to_str().unwrap())?;
+
+        serialized
+            .map
+            .into_iter()
+            .filter(|(date, _)| is_in_range(**date))
+            .try_for_each(|(date, value)| values.push(date, value))?;
+    }
+
+    Ok(values)
+}
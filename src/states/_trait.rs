@@ -1,6 +1,9 @@
 use std::{fmt::Debug, fs, io};
 
-use crate::io::{Binary, OUTPUTS_FOLDER_PATH};
+use crate::{
+    config::Config,
+    io::{staged_path, Binary},
+};
 
 // https://github.com/djkoloski/rust_serialization_benchmark
 pub trait AnyState
@@ -14,7 +17,7 @@ where
     }
 
     fn folder_path() -> String {
-        format!("{OUTPUTS_FOLDER_PATH}/states")
+        format!("{}/states", Config::get().outputs_path)
     }
 
     fn full_path() -> String {
@@ -28,7 +31,7 @@ where
     fn reset(&mut self) -> color_eyre::Result<(), io::Error> {
         self.clear();
 
-        fs::remove_file(Self::full_path())
+        Binary::remove(&Self::full_path())
     }
 
     fn import() -> color_eyre::Result<Self> {
@@ -37,8 +40,9 @@ where
         Binary::import(&Self::full_path())
     }
 
-    fn export(&self) -> color_eyre::Result<()> {
-        Binary::export(&Self::full_path(), self)
+    /// Becomes the current version once the checkpoint at `height` is committed
+    fn stage(&self, height: usize) -> color_eyre::Result<()> {
+        Binary::export(&staged_path(&Self::full_path(), height), self)
     }
 
     fn clear(&mut self);
@@ -0,0 +1,38 @@
+use bitcoin::{hashes::Hash, BlockHash};
+use derive_deref::{Deref, DerefMut};
+use savefile_derive::Savefile;
+
+use super::AnyState;
+
+const UNKNOWN_HASH: [u8; 32] = [0; 32];
+
+/// Hash of every parsed block by height, used to detect reorgs between runs
+#[derive(Default, Deref, DerefMut, Debug, Savefile)]
+pub struct BlockHashes(Vec<[u8; 32]>);
+
+impl BlockHashes {
+    pub fn insert(&mut self, height: usize, hash: BlockHash) {
+        if self.len() <= height {
+            // Heights parsed before hashes were recorded stay unknown
+            self.resize(height + 1, UNKNOWN_HASH);
+        }
+
+        self[height] = hash.to_byte_array();
+    }
+
+    pub fn get_hash(&self, height: usize) -> Option<BlockHash> {
+        self.get(height)
+            .filter(|hash| **hash != UNKNOWN_HASH)
+            .map(|hash| BlockHash::from_byte_array(*hash))
+    }
+}
+
+impl AnyState for BlockHashes {
+    fn name<'a>() -> &'a str {
+        "block_hashes"
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+}
@@ -23,7 +23,7 @@ impl AddressCohortsOutputStates {
         let split_volume = liquidity_classification.split(volume);
 
         let iterate = move |state: &mut SplitByLiquidity<OutputState>| {
-            state.all.iterate(count, volume);
+            state.all.iterate(split_count.all, split_volume.all);
 
             state
                 .illiquid
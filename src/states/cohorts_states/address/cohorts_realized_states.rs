@@ -19,11 +19,11 @@ impl AddressCohortsRealizedStates {
         let profit = realized_data.profit;
         let loss = realized_data.loss;
 
-        let split_profit = liquidity_classification.split(profit);
-        let split_loss = liquidity_classification.split(loss);
+        let split_profit = liquidity_classification.split_f64(profit);
+        let split_loss = liquidity_classification.split_f64(loss);
 
         let iterate = move |state: &mut SplitByLiquidity<RealizedState>| {
-            state.all.iterate(profit, loss);
+            state.all.iterate(split_profit.all, split_loss.all);
 
             state
                 .illiquid
@@ -5,8 +5,10 @@ mod output_state;
 mod price_in_cents_to_amount;
 mod price_paid_state;
 mod realized_state;
+mod sopr_state;
 mod supply_state;
 mod unrealized_state;
+mod urpd;
 mod utxo_state;
 
 pub use durable_states::*;
@@ -16,6 +18,8 @@ pub use output_state::*;
 pub use price_in_cents_to_amount::*;
 pub use price_paid_state::*;
 pub use realized_state::*;
+pub use sopr_state::*;
 pub use supply_state::*;
 pub use unrealized_state::*;
+pub use urpd::*;
 pub use utxo_state::*;
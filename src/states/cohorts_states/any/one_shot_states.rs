@@ -1,4 +1,4 @@
-use super::{PricePaidState, UnrealizedState};
+use super::{PricePaidState, URPDState, UnrealizedState};
 
 #[derive(Default)]
 pub struct OneShotStates {
@@ -6,4 +6,7 @@ pub struct OneShotStates {
 
     pub unrealized_block_state: UnrealizedState,
     pub unrealized_date_state: Option<UnrealizedState>,
+
+    /// At the last block of a date, if enabled in the config
+    pub urpd: Option<URPDState>,
 }
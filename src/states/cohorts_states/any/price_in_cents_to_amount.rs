@@ -2,9 +2,9 @@ use std::collections::BTreeMap;
 
 use derive_deref::{Deref, DerefMut};
 
-use crate::bitcoin::sats_to_btc;
+use crate::{bitcoin::sats_to_btc, config::Config};
 
-use super::{OneShotStates, UnrealizedState};
+use super::{OneShotStates, URPDState, UnrealizedState};
 
 #[derive(Deref, DerefMut, Default, Debug)]
 pub struct PriceInCentsToAmount(BTreeMap<u64, u64>);
@@ -43,6 +43,8 @@ impl PriceInCentsToAmount {
             one_shot_states
                 .unrealized_date_state
                 .replace(UnrealizedState::default());
+
+            one_shot_states.urpd = Config::get().urpd_buckets_per_decade.map(URPDState::new);
         }
 
         let mut processed_amount = 0;
@@ -78,6 +80,10 @@ impl PriceInCentsToAmount {
                         btc_amount,
                     );
                 }
+
+                if let Some(urpd) = one_shot_states.urpd.as_mut() {
+                    urpd.increment(*mean_price_paid_in_cent, *sat_amount);
+                }
             });
 
         if processed_amount != supply {
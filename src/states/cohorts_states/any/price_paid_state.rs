@@ -1,6 +1,6 @@
 #[derive(Default, Debug)]
 pub struct PricePaidState {
-    pub realized_cap: f32,
+    pub realized_cap: f64,
 
     pub pp_05p: Option<f32>,
     pub pp_10p: Option<f32>,
@@ -51,7 +51,7 @@ impl PricePaidState {
             pp_95p,
         } = self;
 
-        *realized_cap += btc_amount * price;
+        *realized_cap += btc_amount as f64 * price as f64;
 
         *processed_amount += sat_amount;
 
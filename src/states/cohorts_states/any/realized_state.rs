@@ -1,11 +1,11 @@
 #[derive(Debug, Default)]
 pub struct RealizedState {
-    pub realized_profit: f32,
-    pub realized_loss: f32,
+    pub realized_profit: f64,
+    pub realized_loss: f64,
 }
 
 impl RealizedState {
-    pub fn iterate(&mut self, realized_profit: f32, realized_loss: f32) {
+    pub fn iterate(&mut self, realized_profit: f64, realized_loss: f64) {
         self.realized_profit += realized_profit;
         self.realized_loss += realized_loss;
     }
@@ -0,0 +1,21 @@
+/// Dollar values of the spent outputs, at the price of their spend and at the price of their creation
+#[derive(Debug, Default)]
+pub struct SOPRState {
+    pub spent_value: f64,
+    pub spent_value_at_creation: f64,
+    /// Without the outputs younger than an hour
+    pub adjusted_spent_value: f64,
+    pub adjusted_spent_value_at_creation: f64,
+}
+
+impl SOPRState {
+    pub fn iterate(&mut self, spent_value: f64, spent_value_at_creation: f64, is_adjusted: bool) {
+        self.spent_value += spent_value;
+        self.spent_value_at_creation += spent_value_at_creation;
+
+        if is_adjusted {
+            self.adjusted_spent_value += spent_value;
+            self.adjusted_spent_value_at_creation += spent_value_at_creation;
+        }
+    }
+}
@@ -3,18 +3,18 @@ use std::ops::Add;
 #[derive(Debug, Default)]
 pub struct UnrealizedState {
     pub supply_in_profit: u64,
-    pub unrealized_profit: f32,
-    pub unrealized_loss: f32,
+    pub unrealized_profit: f64,
+    pub unrealized_loss: f64,
 }
 
 impl UnrealizedState {
     #[inline]
     pub fn iterate(&mut self, price_then: f32, price_now: f32, sat_amount: u64, btc_amount: f32) {
         if price_then < price_now {
-            self.unrealized_profit += btc_amount * (price_now - price_then);
+            self.unrealized_profit += btc_amount as f64 * (price_now - price_then) as f64;
             self.supply_in_profit += sat_amount;
         } else if price_then > price_now {
-            self.unrealized_loss += btc_amount * (price_then - price_now);
+            self.unrealized_loss += btc_amount as f64 * (price_then - price_now) as f64;
         }
     }
 }
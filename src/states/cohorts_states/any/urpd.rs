@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::bitcoin::sats_to_btc_f64;
+
+/// Bucket of the outputs acquired for free, before the first prices
+const ZERO_PRICE_BUCKET: i32 = i32::MIN;
+
+///
+/// UTXO Realized Price Distribution, the supply by price at which it was acquired.
+///
+/// Buckets are log spaced, each one going from `10^(index / buckets_per_decade)` included to the next one excluded.
+///
+#[derive(Debug, Clone)]
+pub struct URPDState {
+    buckets_per_decade: u32,
+    /// Sats by bucket index
+    buckets: BTreeMap<i32, u64>,
+}
+
+#[derive(Serialize)]
+struct URPDSnapshot {
+    buckets_per_decade: u32,
+    /// Lowest price of each bucket with its supply in bitcoin, without the empty ones
+    buckets: Vec<(f64, f64)>,
+}
+
+impl URPDState {
+    pub fn new(buckets_per_decade: u32) -> Self {
+        Self {
+            buckets_per_decade,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    pub fn increment(&mut self, price_in_cents: u64, sat_amount: u64) {
+        *self
+            .buckets
+            .entry(self.bucket_index(price_in_cents))
+            .or_default() += sat_amount;
+    }
+
+    /// Adds the supply of a distribution with the same buckets
+    pub fn merge(&mut self, other: &Self) {
+        other.buckets.iter().for_each(|(index, sat_amount)| {
+            *self.buckets.entry(*index).or_default() += sat_amount;
+        });
+    }
+
+    fn bucket_index(&self, price_in_cents: u64) -> i32 {
+        if price_in_cents == 0 {
+            return ZERO_PRICE_BUCKET;
+        }
+
+        let price = price_in_cents as f64 / 100.0;
+
+        (price.log10() * self.buckets_per_decade as f64).floor() as i32
+    }
+
+    fn bucket_price(&self, index: i32) -> f64 {
+        if index == ZERO_PRICE_BUCKET {
+            return 0.0;
+        }
+
+        10_f64.powf(index as f64 / self.buckets_per_decade as f64)
+    }
+
+    pub fn to_snapshot(&self) -> impl Serialize {
+        URPDSnapshot {
+            buckets_per_decade: self.buckets_per_decade,
+            buckets: self
+                .buckets
+                .iter()
+                .filter(|(_, sat_amount)| **sat_amount != 0)
+                .map(|(index, sat_amount)| {
+                    (self.bucket_price(*index), sats_to_btc_f64(*sat_amount))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_index() {
+        let urpd = URPDState::new(10);
+
+        // Each decade starts a bucket, the cent before it being in the last bucket of the previous one
+        [
+            (10, -10),
+            (100, 0),
+            (1_000, 10),
+            (100_000, 30),
+            (10_000_000_000, 80),
+        ]
+        .into_iter()
+        .for_each(|(price_in_cents, index)| {
+            assert_eq!(urpd.bucket_index(price_in_cents), index);
+            assert_eq!(urpd.bucket_index(price_in_cents - 1), index - 1);
+        });
+
+        assert_eq!(urpd.bucket_index(1), -20);
+        assert_eq!(urpd.bucket_price(30), 1_000.0);
+
+        assert_eq!(urpd.bucket_index(0), ZERO_PRICE_BUCKET);
+        assert_eq!(urpd.bucket_price(ZERO_PRICE_BUCKET), 0.0);
+    }
+}
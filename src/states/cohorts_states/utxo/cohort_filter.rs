@@ -1,21 +1,66 @@
+use crate::{
+    bitcoin::height_to_epoch,
+    config::{Config, UTXOCohortDefinition},
+    utils::{difference_in_days_between_timestamps, timestamp_to_year},
+};
+
+/// What outputs are filtered by, as of a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UTXOAge {
+    pub days_old: u32,
+    /// 0 if no cohort needs it, so that outputs whose age in days didn't change can be skipped
+    pub blocks_old: u32,
+    pub year: u32,
+    pub epoch: u32,
+}
+
+impl UTXOAge {
+    /// Of outputs created at `timestamp` and `height`, as of the block at `last_timestamp` and `last_height`
+    pub fn new(timestamp: u32, height: u32, last_timestamp: u32, last_height: u32) -> Self {
+        Self {
+            days_old: difference_in_days_between_timestamps(timestamp, last_timestamp),
+            blocks_old: if Config::get().needs_blocks_old() {
+                last_height - height
+            } else {
+                0
+            },
+            year: timestamp_to_year(timestamp),
+            epoch: height_to_epoch(height as usize),
+        }
+    }
+}
+
 pub enum UTXOFilter {
     To(u32),
     FromTo { from: u32, to: u32 },
     From(u32),
-    Year(u32),
+    Custom(UTXOCohortDefinition),
 }
 
 impl UTXOCheck for UTXOFilter {
-    fn check(&self, days_old: &u32, year: &u32) -> bool {
+    fn check(&self, age: &UTXOAge) -> bool {
+        let days_old = &age.days_old;
+
         match self {
             UTXOFilter::From(from) => from <= days_old,
             UTXOFilter::To(to) => to > days_old,
             UTXOFilter::FromTo { from, to } => from <= days_old && to > days_old,
-            UTXOFilter::Year(_year) => _year == year,
+            UTXOFilter::Custom(definition) => definition.check(age),
         }
     }
 }
 
+impl UTXOCheck for UTXOCohortDefinition {
+    fn check(&self, age: &UTXOAge) -> bool {
+        self.from_days.is_none_or(|from| from <= age.days_old)
+            && self.to_days.is_none_or(|to| to > age.days_old)
+            && self.from_blocks.is_none_or(|from| from <= age.blocks_old)
+            && self.to_blocks.is_none_or(|to| to > age.blocks_old)
+            && self.from_year.is_none_or(|from| from <= age.year)
+            && self.to_year.is_none_or(|to| to >= age.year)
+    }
+}
+
 pub trait UTXOCheck {
-    fn check(&self, days_old: &u32, year: &u32) -> bool;
+    fn check(&self, age: &UTXOAge) -> bool;
 }
@@ -1,3 +1,5 @@
+use crate::config::Config;
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum UTXOCohortId {
     UpTo1d,
@@ -29,29 +31,19 @@ pub enum UTXOCohortId {
     From1y,
     From10y,
 
-    Year2009,
-    Year2010,
-    Year2011,
-    Year2012,
-    Year2013,
-    Year2014,
-    Year2015,
-    Year2016,
-    Year2017,
-    Year2018,
-    Year2019,
-    Year2020,
-    Year2021,
-    Year2022,
-    Year2023,
-    Year2024,
+    /// Outputs created during the year
+    Year(u32),
+    /// Outputs created during the halving epoch
+    Epoch(u32),
+    /// Index in the cohorts of the config
+    Custom(usize),
 
     ShortTermHolders,
     LongTermHolders,
 }
 
 impl UTXOCohortId {
-    pub fn name(&self) -> &str {
+    pub fn name(&self) -> String {
         match self {
             UTXOCohortId::UpTo1d => "up_to_1d",
             UTXOCohortId::UpTo1w => "up_to_1w",
@@ -82,25 +74,15 @@ impl UTXOCohortId {
             UTXOCohortId::From1y => "from_1y",
             UTXOCohortId::From10y => "from_10y",
 
-            UTXOCohortId::Year2009 => "year_2009",
-            UTXOCohortId::Year2010 => "year_2010",
-            UTXOCohortId::Year2011 => "year_2011",
-            UTXOCohortId::Year2012 => "year_2012",
-            UTXOCohortId::Year2013 => "year_2013",
-            UTXOCohortId::Year2014 => "year_2014",
-            UTXOCohortId::Year2015 => "year_2015",
-            UTXOCohortId::Year2016 => "year_2016",
-            UTXOCohortId::Year2017 => "year_2017",
-            UTXOCohortId::Year2018 => "year_2018",
-            UTXOCohortId::Year2019 => "year_2019",
-            UTXOCohortId::Year2020 => "year_2020",
-            UTXOCohortId::Year2021 => "year_2021",
-            UTXOCohortId::Year2022 => "year_2022",
-            UTXOCohortId::Year2023 => "year_2023",
-            UTXOCohortId::Year2024 => "year_2024",
+            UTXOCohortId::Year(year) => return format!("year_{year}"),
+            UTXOCohortId::Epoch(epoch) => return format!("epoch_{epoch}"),
+            UTXOCohortId::Custom(index) => {
+                return format!("custom_{}", Config::get().utxo_cohorts[*index].name)
+            }
 
             UTXOCohortId::ShortTermHolders => "sth",
             UTXOCohortId::LongTermHolders => "lth",
         }
+        .to_owned()
     }
 }
@@ -5,13 +5,10 @@ use derive_deref::{Deref, DerefMut};
 use crate::{
     parse::BlockData,
     states::{DateDataVec, DurableStates},
-    utils::{
-        convert_price_to_significant_cents, difference_in_days_between_timestamps,
-        timestamp_to_year,
-    },
+    utils::convert_price_to_significant_cents,
 };
 
-use super::{SplitByUTXOCohort, UTXOCohortsOneShotStates};
+use super::{SplitByUTXOCohort, UTXOAge, UTXOCohortsOneShotStates};
 
 #[derive(Default, Deref, DerefMut)]
 pub struct UTXOCohortsDurableStates(SplitByUTXOCohort<DurableStates>);
@@ -48,32 +45,34 @@ impl UTXOCohortsDurableStates {
             return;
         }
 
-        let price_in_cents = convert_price_to_significant_cents(block_data.price);
+        let price_in_cents = convert_price_to_significant_cents(block_data.price as f64);
 
-        let increment_days_old =
-            difference_in_days_between_timestamps(block_data.timestamp, last_block_data.timestamp);
-
-        let increment_year = timestamp_to_year(block_data.timestamp);
+        let increment_age = UTXOAge::new(
+            block_data.timestamp,
+            block_data.height,
+            last_block_data.timestamp,
+            last_block_data.height,
+        );
 
         if let Some(previous_last_block_data) = previous_last_block_data {
             if block_data.height <= previous_last_block_data.height {
                 let previous_block_timestamp = block_data.timestamp
                     - (last_block_data.timestamp - previous_last_block_data.timestamp);
 
-                let decrement_days_old = difference_in_days_between_timestamps(
+                let decrement_age = UTXOAge::new(
                     previous_block_timestamp,
+                    block_data.height,
                     previous_last_block_data.timestamp,
+                    previous_last_block_data.height,
                 );
 
-                let decrement_year = timestamp_to_year(previous_block_timestamp);
-
-                if increment_days_old == decrement_days_old && increment_year == decrement_year {
+                if increment_age == decrement_age {
                     return;
                 }
 
-                let decrement_ids = self.filtered_ids(&decrement_days_old, &decrement_year);
+                let decrement_ids = self.filtered_ids(&decrement_age);
 
-                let increment_ids = self.filtered_ids(&increment_days_old, &increment_year);
+                let increment_ids = self.filtered_ids(&increment_age);
 
                 decrement_ids
                     .iter()
@@ -95,7 +94,7 @@ impl UTXOCohortsDurableStates {
             }
         }
 
-        self.filtered_apply(&increment_days_old, &increment_year, |state| {
+        self.filtered_apply(&increment_age, |state| {
             state.increment(amount, utxo_count, price_in_cents);
         })
     }
@@ -218,70 +217,36 @@ impl UTXOCohortsDurableStates {
                     .compute_one_shot_states(block_price, date_price)
             });
 
-            let year_2009_handle = scope.spawn(|| {
-                self.year_2009
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2010_handle = scope.spawn(|| {
-                self.year_2010
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2011_handle = scope.spawn(|| {
-                self.year_2011
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2012_handle = scope.spawn(|| {
-                self.year_2012
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2013_handle = scope.spawn(|| {
-                self.year_2013
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2014_handle = scope.spawn(|| {
-                self.year_2014
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2015_handle = scope.spawn(|| {
-                self.year_2015
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2016_handle = scope.spawn(|| {
-                self.year_2016
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2017_handle = scope.spawn(|| {
-                self.year_2017
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2018_handle = scope.spawn(|| {
-                self.year_2018
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2019_handle = scope.spawn(|| {
-                self.year_2019
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2020_handle = scope.spawn(|| {
-                self.year_2020
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2021_handle = scope.spawn(|| {
-                self.year_2021
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2022_handle = scope.spawn(|| {
-                self.year_2022
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2023_handle = scope.spawn(|| {
-                self.year_2023
-                    .compute_one_shot_states(block_price, date_price)
-            });
-            let year_2024_handle = scope.spawn(|| {
-                self.year_2024
-                    .compute_one_shot_states(block_price, date_price)
-            });
+            let years_handles = self
+                .years
+                .iter()
+                .map(|(year, state)| {
+                    (
+                        *year,
+                        scope.spawn(|| state.compute_one_shot_states(block_price, date_price)),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let customs_handles = self
+                .customs
+                .iter()
+                .map(|(index, state)| {
+                    (
+                        *index,
+                        scope.spawn(|| state.compute_one_shot_states(block_price, date_price)),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let epochs_handles = self
+                .epochs
+                .iter()
+                .map(|(epoch, state)| {
+                    (
+                        *epoch,
+                        scope.spawn(|| state.compute_one_shot_states(block_price, date_price)),
+                    )
+                })
+                .collect::<Vec<_>>();
 
             UTXOCohortsOneShotStates(SplitByUTXOCohort {
                 sth: sth_handle.join().unwrap(),
@@ -316,22 +281,18 @@ impl UTXOCohortsDurableStates {
                 from_1y: from_1y_handle.join().unwrap(),
                 from_10y: from_10y_handle.join().unwrap(),
 
-                year_2009: year_2009_handle.join().unwrap(),
-                year_2010: year_2010_handle.join().unwrap(),
-                year_2011: year_2011_handle.join().unwrap(),
-                year_2012: year_2012_handle.join().unwrap(),
-                year_2013: year_2013_handle.join().unwrap(),
-                year_2014: year_2014_handle.join().unwrap(),
-                year_2015: year_2015_handle.join().unwrap(),
-                year_2016: year_2016_handle.join().unwrap(),
-                year_2017: year_2017_handle.join().unwrap(),
-                year_2018: year_2018_handle.join().unwrap(),
-                year_2019: year_2019_handle.join().unwrap(),
-                year_2020: year_2020_handle.join().unwrap(),
-                year_2021: year_2021_handle.join().unwrap(),
-                year_2022: year_2022_handle.join().unwrap(),
-                year_2023: year_2023_handle.join().unwrap(),
-                year_2024: year_2024_handle.join().unwrap(),
+                years: years_handles
+                    .into_iter()
+                    .map(|(year, handle)| (year, handle.join().unwrap()))
+                    .collect(),
+                epochs: epochs_handles
+                    .into_iter()
+                    .map(|(epoch, handle)| (epoch, handle.join().unwrap()))
+                    .collect(),
+                customs: customs_handles
+                    .into_iter()
+                    .map(|(index, handle)| (index, handle.join().unwrap()))
+                    .collect(),
             })
         })
     }
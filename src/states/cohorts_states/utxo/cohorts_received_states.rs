@@ -7,10 +7,9 @@ use crate::{
     bitcoin::sats_to_btc,
     parse::BlockPath,
     states::{DateDataVec, OutputState},
-    utils::{difference_in_days_between_timestamps, timestamp_to_year},
 };
 
-use super::SplitByUTXOCohort;
+use super::{SplitByUTXOCohort, UTXOAge};
 
 #[derive(Deref, DerefMut, Default)]
 pub struct UTXOCohortsReceivedStates(SplitByUTXOCohort<OutputState>);
@@ -37,16 +36,16 @@ impl UTXOCohortsReceivedStates {
                     (block_data, data)
                 })
                 .for_each(|(block_data, received_data)| {
-                    let days_old = difference_in_days_between_timestamps(
+                    let age = UTXOAge::new(
                         block_data.timestamp,
+                        block_data.height,
                         last_block_data.timestamp,
+                        last_block_data.height,
                     );
 
-                    let year = timestamp_to_year(block_data.timestamp);
-
                     let volume = sats_to_btc(received_data.volume);
 
-                    self.filtered_apply(&days_old, &year, |state| {
+                    self.filtered_apply(&age, |state| {
                         state.iterate(received_data.count as f32, volume);
                     });
                 })
@@ -4,18 +4,21 @@ use derive_deref::{Deref, DerefMut};
 
 use crate::{
     actions::SpentData,
-    bitcoin::sats_to_btc,
+    bitcoin::{sats_to_btc, sats_to_btc_f64},
     parse::BlockPath,
-    states::{DateDataVec, InputState, RealizedState},
-    utils::{difference_in_days_between_timestamps, timestamp_to_year},
+    states::{DateDataVec, InputState, RealizedState, SOPRState},
 };
 
-use super::SplitByUTXOCohort;
+use super::{SplitByUTXOCohort, UTXOAge};
+
+/// In seconds, younger outputs are left out of the adjusted SOPR
+const ADJUSTED_SOPR_MIN_AGE: u32 = 60 * 60;
 
 #[derive(Default, Debug)]
 pub struct SentState {
     pub input: InputState,
     pub realized: RealizedState,
+    pub sopr: SOPRState,
 }
 
 #[derive(Deref, DerefMut, Default)]
@@ -44,22 +47,28 @@ impl UTXOCohortsSentStates {
                     (block_data, data)
                 })
                 .for_each(|(block_data, spent_data)| {
-                    let days_old = difference_in_days_between_timestamps(
+                    let age = UTXOAge::new(
                         block_data.timestamp,
+                        block_data.height,
                         last_block_data.timestamp,
+                        last_block_data.height,
                     );
 
-                    let year = timestamp_to_year(block_data.timestamp);
-
                     let previous_price = block_data.price;
 
+                    let is_adjusted = last_block_data
+                        .timestamp
+                        .saturating_sub(block_data.timestamp)
+                        >= ADJUSTED_SOPR_MIN_AGE;
+
                     let btc_spent = sats_to_btc(spent_data.volume);
+                    let btc_spent_f64 = sats_to_btc_f64(spent_data.volume);
 
-                    self.filtered_apply(&days_old, &year, |state| {
+                    self.filtered_apply(&age, |state| {
                         state.input.iterate(spent_data.count as f32, btc_spent);
 
-                        let previous_dollar_amount = previous_price * btc_spent;
-                        let current_dollar_amount = current_price * btc_spent;
+                        let previous_dollar_amount = previous_price as f64 * btc_spent_f64;
+                        let current_dollar_amount = current_price as f64 * btc_spent_f64;
 
                         if previous_dollar_amount < current_dollar_amount {
                             state.realized.realized_profit +=
@@ -68,6 +77,12 @@ impl UTXOCohortsSentStates {
                             state.realized.realized_loss +=
                                 previous_dollar_amount - current_dollar_amount;
                         }
+
+                        state.sopr.iterate(
+                            current_dollar_amount,
+                            previous_dollar_amount,
+                            is_adjusted,
+                        );
                     })
                 })
         }
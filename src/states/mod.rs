@@ -2,6 +2,7 @@ use std::thread;
 
 mod _trait;
 mod address_index_to_address_data;
+mod block_hashes;
 mod cohorts_states;
 mod counters;
 mod date_data_vec;
@@ -11,6 +12,7 @@ mod txout_index_to_sats;
 
 pub use _trait::*;
 use address_index_to_address_data::*;
+use block_hashes::*;
 pub use cohorts_states::*;
 use counters::*;
 use date_data_vec::*;
@@ -21,6 +23,7 @@ use txout_index_to_sats::*;
 #[derive(Default)]
 pub struct States {
     pub address_index_to_address_data: AddressIndexToAddressData,
+    pub block_hashes: BlockHashes,
     pub counters: Counters,
     pub date_data_vec: DateDataVec,
     pub address_cohorts_durable_states: AddressCohortsDurableStates,
@@ -42,6 +45,9 @@ impl States {
 
         let date_data_vec_handle = thread::spawn(DateDataVec::import);
 
+        // Missing for states exported before hashes were recorded
+        let block_hashes = BlockHashes::import().unwrap_or_default();
+
         let counters = Counters::import()?;
 
         let date_data_vec = date_data_vec_handle.join().unwrap()?;
@@ -62,6 +68,7 @@ impl States {
         Ok(Self {
             address_cohorts_durable_states,
             address_index_to_address_data,
+            block_hashes,
             counters,
             date_data_vec,
             tx_index_to_tx_data,
@@ -75,6 +82,7 @@ impl States {
         println!("Reseting all states...");
 
         let _ = self.address_index_to_address_data.reset();
+        let _ = self.block_hashes.reset();
         let _ = self.counters.reset();
         let _ = self.date_data_vec.reset();
         let _ = self.tx_index_to_tx_data.reset();
@@ -85,14 +93,15 @@ impl States {
         self.utxo_cohorts_durable_states = UTXOCohortsDurableStates::default();
     }
 
-    pub fn export(&self) -> color_eyre::Result<()> {
+    pub fn stage(&self, height: usize) -> color_eyre::Result<()> {
         thread::scope(|s| {
-            s.spawn(|| self.address_index_to_address_data.export().unwrap());
-            s.spawn(|| self.counters.export().unwrap());
-            s.spawn(|| self.date_data_vec.export().unwrap());
-            s.spawn(|| self.tx_index_to_tx_data.export().unwrap());
-            s.spawn(|| self.txout_index_to_address_index.export().unwrap());
-            s.spawn(|| self.txout_index_to_sats.export().unwrap());
+            s.spawn(|| self.address_index_to_address_data.stage(height).unwrap());
+            s.spawn(|| self.block_hashes.stage(height).unwrap());
+            s.spawn(|| self.counters.stage(height).unwrap());
+            s.spawn(|| self.date_data_vec.stage(height).unwrap());
+            s.spawn(|| self.tx_index_to_tx_data.stage(height).unwrap());
+            s.spawn(|| self.txout_index_to_address_index.stage(height).unwrap());
+            s.spawn(|| self.txout_index_to_sats.stage(height).unwrap());
         });
 
         Ok(())
@@ -1,4 +1,4 @@
-pub fn convert_price_to_significant_cents(price: f32) -> u64 {
+pub fn convert_price_to_significant_cents(price: f64) -> u64 {
     let mut price_in_cents = (price * 100.0).round() as u64;
 
     let ilog10 = price_in_cents.checked_ilog10().unwrap_or(0) as i32;